@@ -24,6 +24,17 @@ impl Display for FunctionId {
     }
 }
 
+/// Identifies a position in a function's code, which can be jumped to.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Zeroable, Pod)]
+pub struct Label(u32);
+
+impl Display for Label {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "L{}", self.0)
+    }
+}
+
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Zeroable, Pod)]
 pub struct UnstableTypeId(u64);
@@ -33,4 +44,24 @@ impl UnstableTypeId {
     pub const VOID: Self = Self(0);
     /// The 32-bit natural type.
     pub const NAT32: Self = Self(1);
+    /// The 64-bit natural type.
+    pub const NAT64: Self = Self(2);
+    /// The 16-bit natural type.
+    pub const NAT16: Self = Self(3);
+    /// The 8-bit natural type.
+    pub const NAT8: Self = Self(4);
+    /// The 64-bit integer type.
+    pub const INT64: Self = Self(5);
+    /// The 32-bit integer type.
+    pub const INT32: Self = Self(6);
+    /// The 16-bit integer type.
+    pub const INT16: Self = Self(7);
+    /// The 8-bit integer type.
+    pub const INT8: Self = Self(8);
+    /// The 64-bit floating point type.
+    pub const FLOAT64: Self = Self(9);
+    /// The 32-bit floating point type.
+    pub const FLOAT32: Self = Self(10);
+    /// The 16-bit floating point type.
+    pub const FLOAT16: Self = Self(11);
 }
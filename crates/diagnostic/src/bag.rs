@@ -0,0 +1,101 @@
+use crate::{Diagnostic, Severity};
+
+/// Collection of diagnostics emitted during a compilation stage.
+///
+/// A bag can be given a maximum number of errors. Once more errors than that are pushed, the bag
+/// stops accepting diagnostics that are not errors, so that warnings don't drown out the errors
+/// the user has to fix first. A note saying how many diagnostics were dropped is appended to the
+/// end of the bag when it's turned into a [`Vec`].
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticBag {
+    diagnostics: Vec<Diagnostic>,
+    max_errors: Option<usize>,
+    error_count: usize,
+    suppressed_count: usize,
+}
+
+impl DiagnosticBag {
+    /// Construct an empty bag with no limit on the number of errors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct an empty bag, which stops accepting non-error diagnostics once more than
+    /// `max_errors` errors are pushed.
+    pub fn with_max_errors(max_errors: usize) -> Self {
+        Self {
+            max_errors: Some(max_errors),
+            ..Self::default()
+        }
+    }
+
+    /// Add a diagnostic to the bag.
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        if is_error(&diagnostic) {
+            self.error_count += 1;
+        } else if self.has_too_many_errors() {
+            self.suppressed_count += 1;
+            return;
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Returns whether any diagnostic in the bag is a [`Bug`][Severity::Bug] or an
+    /// [`Error`][Severity::Error].
+    pub fn has_errors(&self) -> bool {
+        self.error_count > 0
+    }
+
+    /// Returns whether the bag contains no diagnostics.
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Iterate over the diagnostics in the bag.
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    /// Turn the bag into a [`Vec`] of diagnostics, with a "too many errors" note at the end if there
+    /// were more errors than the bag's limit. The note says how many diagnostics were dropped, if
+    /// any were.
+    pub fn into_vec(mut self) -> Vec<Diagnostic> {
+        if let Some(max_errors) = self.max_errors {
+            if self.has_too_many_errors() {
+                let mut message = format!("too many errors (more than {max_errors})");
+                if self.suppressed_count > 0 {
+                    let plural = if self.suppressed_count == 1 { "" } else { "s" };
+                    message += &format!(
+                        "; {} other diagnostic{plural} not shown",
+                        self.suppressed_count
+                    );
+                }
+                self.diagnostics.push(Severity::Note.diagnostic(message));
+            }
+        }
+        self.diagnostics
+    }
+
+    fn has_too_many_errors(&self) -> bool {
+        self.max_errors
+            .is_some_and(|max_errors| self.error_count > max_errors)
+    }
+}
+
+fn is_error(diagnostic: &Diagnostic) -> bool {
+    diagnostic.severity.is_fatal()
+}
+
+impl Extend<Diagnostic> for DiagnosticBag {
+    fn extend<T: IntoIterator<Item = Diagnostic>>(&mut self, iter: T) {
+        for diagnostic in iter {
+            self.push(diagnostic);
+        }
+    }
+}
+
+impl From<DiagnosticBag> for Vec<Diagnostic> {
+    fn from(bag: DiagnosticBag) -> Self {
+        bag.into_vec()
+    }
+}
@@ -21,12 +21,20 @@ impl<'a> DiagnosableSources<'a> {
             sources,
             line_starts: HashMap::new(),
         };
+        diagnosable_sources.add_diagnostics(diagnostics);
+        diagnosable_sources
+    }
+
+    fn add_diagnostics(&mut self, diagnostics: &[Diagnostic]) {
         for diagnostic in diagnostics {
             for label in &diagnostic.labels {
-                diagnosable_sources.add_line_starts(label.source_span.file_id);
+                self.add_line_starts(label.source_span.file_id);
             }
+            for suggestion in &diagnostic.suggestions {
+                self.add_line_starts(suggestion.source_span.file_id);
+            }
+            self.add_diagnostics(&diagnostic.children);
         }
-        diagnosable_sources
     }
 
     fn add_line_starts(&mut self, file_id: FileId) {
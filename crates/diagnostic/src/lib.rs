@@ -1,14 +1,23 @@
 //! Rich, structured diagnostic message support, inspired by rustc.
 
+mod bag;
 pub(crate) mod files;
+#[cfg(feature = "lsp")]
+mod lsp;
 mod render;
 
 use std::fmt;
 
 use rokugo_source_code::SourceSpan;
 
+pub use bag::DiagnosticBag;
 pub use render::render;
+#[cfg(feature = "json")]
+pub use render::render_json;
+pub use render::render_with_options;
+pub use render::Backend;
 pub use render::Output;
+pub use render::RenderOptions;
 use rokugo_source_code::Sources;
 
 /// Diagnostic severity.
@@ -82,6 +91,28 @@ pub fn note(kind: NoteKind, message: impl Into<String>) -> Note {
     }
 }
 
+/// How confident we are that applying a [`Suggestion`] results in what the user intended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended, and can be applied automatically.
+    MachineApplicable,
+    /// The suggestion may be what the user intended, but it is uncertain. It should only be
+    /// applied after the user confirms it.
+    MaybeIncorrect,
+    /// The suggestion contains placeholders that the user has to fill in themselves.
+    HasPlaceholders,
+}
+
+/// Edit to source code that fixes the issue a diagnostic is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// Span of source code to be replaced.
+    pub source_span: SourceSpan,
+    /// Text to replace the span with.
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
 /// A structured diagnostic.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Diagnostic {
@@ -103,9 +134,18 @@ pub struct Diagnostic {
     /// Child diagnostics. These are emitted along with this diagnostic and should be considered
     /// extensions of what this diagnostic has to say.
     pub children: Vec<Diagnostic>,
+    /// Suggested edits that fix the issue. Tools such as editors may offer to apply these
+    /// automatically.
+    pub suggestions: Vec<Suggestion>,
 }
 
 impl Severity {
+    /// Returns whether a diagnostic of this severity prevents compilation from producing output,
+    /// which is the case for [`Bug`][Severity::Bug] and [`Error`][Severity::Error].
+    pub fn is_fatal(self) -> bool {
+        matches!(self, Severity::Bug | Severity::Error)
+    }
+
     /// Construct a diagnostic with this severity and a message.
     pub fn diagnostic(self, message: impl Into<String>) -> Diagnostic {
         Diagnostic {
@@ -114,11 +154,26 @@ impl Severity {
             labels: vec![],
             notes: vec![],
             children: vec![],
+            suggestions: vec![],
         }
     }
 }
 
 impl Diagnostic {
+    /// Construct an error with a single primary label.
+    ///
+    /// This is shorthand for `Severity::Error.diagnostic(message)` followed by
+    /// `.with_label(Importance::Primary.label(source_span, label))`.
+    pub fn error_at(
+        source_span: SourceSpan,
+        message: impl Into<String>,
+        label: impl Into<String>,
+    ) -> Self {
+        Severity::Error
+            .diagnostic(message)
+            .with_label(Importance::Primary.label(source_span, label))
+    }
+
     /// Add a label to this diagnostic.
     pub fn with_label(mut self, label: Label) -> Self {
         self.labels.push(label);
@@ -136,16 +191,75 @@ impl Diagnostic {
         self.children.push(child);
         self
     }
+
+    /// Add several labels to this diagnostic.
+    pub fn with_labels(mut self, labels: impl IntoIterator<Item = Label>) -> Self {
+        self.labels.extend(labels);
+        self
+    }
+
+    /// Add several notes to this diagnostic.
+    pub fn with_notes(mut self, notes: impl IntoIterator<Item = Note>) -> Self {
+        self.notes.extend(notes);
+        self
+    }
+
+    /// Add several children to this diagnostic.
+    pub fn with_children(mut self, children: impl IntoIterator<Item = Diagnostic>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// Add a suggestion to this diagnostic.
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
+    /// Render this diagnostic to a string, including snippets of the source code its labels
+    /// point to.
+    pub fn render_to_string(&self, sources: &Sources, output: Output) -> String {
+        let render_bytes = render::render(output, sources, vec![self.clone()]);
+        String::from_utf8_lossy(&render_bytes).into_owned()
+    }
+
+    /// Returns whether this diagnostic or any of its children is [fatal][Severity::is_fatal].
+    pub fn is_fatal(&self) -> bool {
+        self.severity.is_fatal() || self.children.iter().any(Diagnostic::is_fatal)
+    }
+
+    /// Returns the span of the first primary label, if there is one.
+    pub fn primary_span(&self) -> Option<&SourceSpan> {
+        self.labels
+            .iter()
+            .find(|label| label.importance == Importance::Primary)
+            .map(|label| &label.source_span)
+    }
+}
+
+/// Sort diagnostics by the position of their primary label, such that they appear top-to-bottom.
+///
+/// Diagnostics are ordered by [`FileId`][rokugo_source_code::FileId] first, and then by where
+/// their primary label starts. Diagnostics without a primary label are sorted last. The sort is
+/// stable, so diagnostics at the same position retain their original order.
+pub fn sort_by_position(diagnostics: &mut [Diagnostic]) {
+    diagnostics.sort_by_key(|diagnostic| match diagnostic.primary_span() {
+        Some(span) => (false, Some(span.file_id), span.span.start),
+        None => (true, None, 0),
+    });
 }
 
+/// Renders the diagnostic as plain text, without access to any source code.
+///
+/// This is only suitable for diagnostics whose labels and suggestions (including those of their
+/// children) do not point to any files; rendering one that does panics, since the files cannot be
+/// found. Use [`Diagnostic::render_to_string`] to render such diagnostics.
 impl fmt::Display for Diagnostic {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let render_bytes = render::render(
-            render::Output::Plain,
-            &Sources::default(),
-            vec![self.clone()],
-        );
-        writeln!(f, "{}", String::from_utf8_lossy(&render_bytes))?;
-        Ok(())
+        writeln!(
+            f,
+            "{}",
+            self.render_to_string(&Sources::default(), Output::Plain)
+        )
     }
 }
@@ -0,0 +1,114 @@
+//! Conversion of diagnostics to the Language Server Protocol's representation.
+
+use std::path::Path;
+
+use lsp_types::{DiagnosticRelatedInformation, DiagnosticSeverity, Location, Position, Range, Url};
+use rokugo_source_code::{SourceSpan, Sources};
+
+use crate::{Diagnostic, Importance, NoteKind, Severity};
+
+impl Diagnostic {
+    /// Convert this diagnostic to an LSP diagnostic.
+    ///
+    /// The diagnostic's range is that of its primary label. Secondary labels and children become
+    /// related information, and notes are appended to the message, since LSP has no equivalent
+    /// for them. Children without any labels are appended to the message as well.
+    pub fn to_lsp(&self, sources: &Sources) -> lsp_types::Diagnostic {
+        let mut message = self.message.clone();
+        append_notes(&mut message, self);
+
+        let mut related_information = vec![];
+        for label in &self.labels {
+            if label.importance == Importance::Secondary {
+                if let Some(location) = location(sources, &label.source_span) {
+                    related_information.push(DiagnosticRelatedInformation {
+                        location,
+                        message: label.message.clone(),
+                    });
+                }
+            }
+        }
+        for child in &self.children {
+            let mut child_message = format!("{}: {}", severity_name(child.severity), child.message);
+            append_notes(&mut child_message, child);
+            match child
+                .primary_span()
+                .and_then(|span| location(sources, span))
+            {
+                Some(location) => related_information.push(DiagnosticRelatedInformation {
+                    location,
+                    message: child_message,
+                }),
+                None => {
+                    message.push('\n');
+                    message.push_str(&child_message);
+                }
+            }
+        }
+
+        lsp_types::Diagnostic {
+            range: self
+                .primary_span()
+                .map(|span| range(sources, span))
+                .unwrap_or_default(),
+            severity: Some(match self.severity {
+                Severity::Bug | Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+                Severity::Note => DiagnosticSeverity::INFORMATION,
+                Severity::Help => DiagnosticSeverity::HINT,
+            }),
+            source: Some("rokugo".into()),
+            message,
+            related_information: (!related_information.is_empty()).then_some(related_information),
+            ..Default::default()
+        }
+    }
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug => "bug",
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+        Severity::Help => "help",
+    }
+}
+
+fn append_notes(message: &mut String, diagnostic: &Diagnostic) {
+    for note in &diagnostic.notes {
+        message.push('\n');
+        if note.kind == NoteKind::Note {
+            message.push_str("note: ");
+        }
+        message.push_str(&note.message);
+    }
+}
+
+/// Convert a byte offset to an LSP position. LSP counts characters in UTF-16 code units.
+fn position(source: &str, offset: usize) -> Position {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    Position {
+        line: before.matches('\n').count() as u32,
+        character: before[line_start..].encode_utf16().count() as u32,
+    }
+}
+
+fn range(sources: &Sources, source_span: &SourceSpan) -> Range {
+    let source = &sources.get(source_span.file_id).source;
+    Range {
+        start: position(source, source_span.span.start),
+        end: position(source, source_span.span.end),
+    }
+}
+
+/// Returns `None` if the file name cannot be turned into a `file://` URI.
+fn location(sources: &Sources, source_span: &SourceSpan) -> Option<Location> {
+    let filename = &sources.get(source_span.file_id).filename;
+    let path = std::path::absolute(Path::new(filename)).ok()?;
+    Some(Location {
+        uri: Url::from_file_path(path).ok()?,
+        range: range(sources, source_span),
+    })
+}
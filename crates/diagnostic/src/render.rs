@@ -1,79 +1,137 @@
-use codespan_reporting::term::{
-    termcolor::{Ansi, ColorChoice, NoColor, StandardStream, WriteColor},
-    Config,
-};
-use rokugo_source_code::Sources;
-use tracing::error;
+mod codespan;
+#[cfg(feature = "json")]
+mod json;
+mod snippets;
 
-use crate::{files::DiagnosableSources, Diagnostic, Importance, NoteKind, Severity};
+use codespan_reporting::term::termcolor::{ColorChoice, StandardStream, WriteColor};
+use rokugo_source_code::{FileId, Sources};
+
+use crate::{sort_by_position, Diagnostic, Suggestion};
+
+#[cfg(feature = "json")]
+pub use json::render_json;
 
 /// Kind of output that should be rendered.
 ///
 /// Note that if stdout is incapable of rendering color, output will be set to [`Plain`][`Output::Plain`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Output {
+    #[default]
     Plain,
     Colored,
 }
 
+/// Library used for laying out rendered diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Render diagnostics using `codespan-reporting`.
+    #[default]
+    Codespan,
+    /// Render diagnostics using `annotate-snippets`, matching the layout used by rustc.
+    AnnotateSnippets,
+}
+
+/// Options for [`render_with_options`].
+///
+/// The default renders plain output with the default [`Backend`], in the order the diagnostics
+/// were given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderOptions {
+    pub output: Output,
+    pub backend: Backend,
+    /// Group diagnostics by the file their primary label points to, and render a header with the
+    /// file's name above each group.
+    ///
+    /// Groups are sorted by file name, and diagnostics within a group are sorted by position.
+    /// Diagnostics without a primary label are rendered last, in an `unattached` group.
+    pub group_by_file: bool,
+    /// Sort diagnostics by position before rendering. See [`sort_by_position`].
+    pub sort_by_position: bool,
+}
+
+/// Rendering backend, turning diagnostics into bytes.
+trait Renderer {
+    fn render(&self, output: Output, sources: &Sources, diagnostics: Vec<Diagnostic>) -> Vec<u8>;
+}
+
+/// Message shown next to a suggestion's span in human-readable output.
+fn suggestion_message(suggestion: &Suggestion) -> String {
+    if suggestion.replacement.is_empty() {
+        "help: remove this".into()
+    } else {
+        format!("help: replace with `{}`", suggestion.replacement)
+    }
+}
+
 /// Render diagnostics to a buffer of bytes.
 /// This buffer of bytes can later be written out to stdout or a file.
-pub fn render(mut output: Output, sources: &Sources, diagnostics: Vec<Diagnostic>) -> Vec<u8> {
+pub fn render(output: Output, sources: &Sources, diagnostics: Vec<Diagnostic>) -> Vec<u8> {
+    render_with_options(
+        RenderOptions {
+            output,
+            ..RenderOptions::default()
+        },
+        sources,
+        diagnostics,
+    )
+}
+
+/// Same as [`render`], but configured through [`RenderOptions`]: picking the [`Backend`], and
+/// optionally sorting diagnostics by position or grouping them by file.
+pub fn render_with_options(
+    mut options: RenderOptions,
+    sources: &Sources,
+    mut diagnostics: Vec<Diagnostic>,
+) -> Vec<u8> {
+    if options.sort_by_position {
+        sort_by_position(&mut diagnostics);
+    }
+
     if !StandardStream::stdout(ColorChoice::Auto).supports_color() {
-        output = Output::Plain;
+        options.output = Output::Plain;
     }
 
-    let mut plain = NoColor::new(vec![]);
-    let mut colored = Ansi::new(vec![]);
-    let stream: &mut dyn WriteColor = match output {
-        Output::Plain => &mut plain,
-        Output::Colored => &mut colored,
+    let renderer: &dyn Renderer = match options.backend {
+        Backend::Codespan => &codespan::Codespan,
+        Backend::AnnotateSnippets => &snippets::AnnotateSnippets,
     };
+    if !options.group_by_file {
+        return renderer.render(options.output, sources, diagnostics);
+    }
 
-    let files = DiagnosableSources::new(sources, &diagnostics);
-    for diagnostic in diagnostics {
-        let diagnostic = codespan_reporting::diagnostic::Diagnostic {
-            severity: match diagnostic.severity {
-                Severity::Bug => codespan_reporting::diagnostic::Severity::Bug,
-                Severity::Error => codespan_reporting::diagnostic::Severity::Error,
-                Severity::Warning => codespan_reporting::diagnostic::Severity::Warning,
-                Severity::Note => codespan_reporting::diagnostic::Severity::Note,
-                Severity::Help => codespan_reporting::diagnostic::Severity::Help,
-            },
-            code: None,
-            message: diagnostic.message,
-            labels: diagnostic
-                .labels
-                .into_iter()
-                .map(|label| codespan_reporting::diagnostic::Label {
-                    style: match label.importance {
-                        Importance::Primary => codespan_reporting::diagnostic::LabelStyle::Primary,
-                        Importance::Secondary => {
-                            codespan_reporting::diagnostic::LabelStyle::Secondary
-                        }
-                    },
-                    file_id: label.source_span.file_id,
-                    range: label.source_span.span,
-                    message: label.message,
-                })
-                .collect(),
-            notes: diagnostic
-                .notes
-                .into_iter()
-                .map(|note| match note.kind {
-                    NoteKind::Context => note.message,
-                    NoteKind::Note => format!("note: {}", note.message),
-                })
-                .collect(),
+    let mut buffer = vec![];
+    for (file_id, group) in group_by_file(sources, diagnostics) {
+        let name = match file_id {
+            Some(file_id) => &sources.get(file_id).filename,
+            None => "unattached",
         };
-        match codespan_reporting::term::emit(stream, &Config::default(), &files, &diagnostic) {
-            Ok(_) => (),
-            Err(err) => error!(?diagnostic, ?err, "could not emit diagnostic"),
+        buffer.extend_from_slice(format!("── {name} ──\n\n").as_bytes());
+        buffer.extend(renderer.render(options.output, sources, group));
+    }
+    buffer
+}
+
+/// Bucket diagnostics by the file their primary label points to.
+fn group_by_file(
+    sources: &Sources,
+    diagnostics: Vec<Diagnostic>,
+) -> Vec<(Option<FileId>, Vec<Diagnostic>)> {
+    let mut groups: Vec<(Option<FileId>, Vec<Diagnostic>)> = vec![];
+    for diagnostic in diagnostics {
+        let file_id = diagnostic.primary_span().map(|span| span.file_id);
+        match groups.iter_mut().find(|(id, _)| *id == file_id) {
+            Some((_, group)) => group.push(diagnostic),
+            None => groups.push((file_id, vec![diagnostic])),
         }
     }
 
-    match output {
-        Output::Plain => plain.into_inner(),
-        Output::Colored => colored.into_inner(),
+    // Sorting is stable, so diagnostics at the same position stay in emission order.
+    groups.sort_by(|(a, _), (b, _)| match (a, b) {
+        (Some(a), Some(b)) => sources.get(*a).filename.cmp(&sources.get(*b).filename),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+    for (_, group) in &mut groups {
+        sort_by_position(group);
     }
+    groups
 }
@@ -0,0 +1,183 @@
+use codespan_reporting::files::Files;
+use rokugo_source_code::{SourceSpan, Sources};
+use serde::Serialize;
+use tracing::error;
+
+use crate::{
+    files::DiagnosableSources, Applicability, Diagnostic, Importance, Label, Note, NoteKind,
+    Severity, Suggestion,
+};
+
+/// Render diagnostics to a JSON array, for consumption by other programs such as editors and
+/// continuous integration tools. Requires the `json` feature.
+///
+/// Each diagnostic is an object with the following fields. Field names are stable and will not
+/// change without notice.
+///
+/// - `severity`: one of `"bug"`, `"error"`, `"warning"`, `"note"`, or `"help"`.
+/// - `message`: the diagnostic's message.
+/// - `labels`: array of labels, each being an object with the fields:
+///   - `importance`: either `"primary"` or `"secondary"`.
+///   - `file`: name of the file the label points to.
+///   - `start` and `end`: byte range of the label within the file.
+///   - `line` and `column`: 1-based line and column the label starts at.
+///   - `end_line` and `end_column`: 1-based line and column the label ends at.
+///   - `message`: the label's message, which may be empty.
+/// - `notes`: array of notes, each being an object with the fields:
+///   - `kind`: either `"context"` or `"note"`.
+///   - `message`: the note's message.
+/// - `children`: array of child diagnostics, with the same fields as top-level diagnostics.
+/// - `suggestions`: array of suggested edits, each being an object with the fields:
+///   - `file`, `start`, `end`, `line`, `column`, `end_line`, and `end_column`: the span to be
+///     replaced, same as with labels.
+///   - `replacement`: text to replace the span with.
+///   - `applicability`: one of `"machine-applicable"`, `"maybe-incorrect"`, or
+///     `"has-placeholders"`.
+pub fn render_json(sources: &Sources, diagnostics: Vec<Diagnostic>) -> String {
+    let files = DiagnosableSources::new(sources, &diagnostics);
+    let diagnostics: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| JsonDiagnostic::new(&files, diagnostic))
+        .collect();
+    serde_json::to_string(&diagnostics).expect("diagnostics should always serialize to JSON")
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    severity: &'static str,
+    message: &'a str,
+    labels: Vec<JsonLabel<'a>>,
+    notes: Vec<JsonNote<'a>>,
+    children: Vec<JsonDiagnostic<'a>>,
+    suggestions: Vec<JsonSuggestion<'a>>,
+}
+
+impl<'a> JsonDiagnostic<'a> {
+    fn new(files: &'a DiagnosableSources<'a>, diagnostic: &'a Diagnostic) -> Self {
+        Self {
+            severity: match diagnostic.severity {
+                Severity::Bug => "bug",
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Note => "note",
+                Severity::Help => "help",
+            },
+            message: &diagnostic.message,
+            labels: diagnostic
+                .labels
+                .iter()
+                .map(|label| JsonLabel::new(files, label))
+                .collect(),
+            notes: diagnostic.notes.iter().map(JsonNote::new).collect(),
+            children: diagnostic
+                .children
+                .iter()
+                .map(|child| JsonDiagnostic::new(files, child))
+                .collect(),
+            suggestions: diagnostic
+                .suggestions
+                .iter()
+                .map(|suggestion| JsonSuggestion::new(files, suggestion))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonSpan<'a> {
+    file: &'a str,
+    start: usize,
+    end: usize,
+    line: usize,
+    column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+impl<'a> JsonSpan<'a> {
+    fn new(files: &'a DiagnosableSources<'a>, source_span: &SourceSpan) -> Self {
+        let file_id = source_span.file_id;
+        let span = &source_span.span;
+        let location = |byte_index| match files.location(file_id, byte_index) {
+            Ok(location) => (location.line_number, location.column_number),
+            Err(err) => {
+                error!(?source_span, ?err, "could not resolve span location");
+                (0, 0)
+            }
+        };
+        let (line, column) = location(span.start);
+        let (end_line, end_column) = location(span.end);
+        Self {
+            file: files
+                .name(file_id)
+                .expect("file names are always available"),
+            start: span.start,
+            end: span.end,
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonLabel<'a> {
+    importance: &'static str,
+    #[serde(flatten)]
+    span: JsonSpan<'a>,
+    message: &'a str,
+}
+
+impl<'a> JsonLabel<'a> {
+    fn new(files: &'a DiagnosableSources<'a>, label: &'a Label) -> Self {
+        Self {
+            importance: match label.importance {
+                Importance::Primary => "primary",
+                Importance::Secondary => "secondary",
+            },
+            span: JsonSpan::new(files, &label.source_span),
+            message: &label.message,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonSuggestion<'a> {
+    #[serde(flatten)]
+    span: JsonSpan<'a>,
+    replacement: &'a str,
+    applicability: &'static str,
+}
+
+impl<'a> JsonSuggestion<'a> {
+    fn new(files: &'a DiagnosableSources<'a>, suggestion: &'a Suggestion) -> Self {
+        Self {
+            span: JsonSpan::new(files, &suggestion.source_span),
+            replacement: &suggestion.replacement,
+            applicability: match suggestion.applicability {
+                Applicability::MachineApplicable => "machine-applicable",
+                Applicability::MaybeIncorrect => "maybe-incorrect",
+                Applicability::HasPlaceholders => "has-placeholders",
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonNote<'a> {
+    kind: &'static str,
+    message: &'a str,
+}
+
+impl<'a> JsonNote<'a> {
+    fn new(note: &'a Note) -> Self {
+        Self {
+            kind: match note.kind {
+                NoteKind::Context => "context",
+                NoteKind::Note => "note",
+            },
+            message: &note.message,
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use annotate_snippets::{Level, Message, Snippet};
+use rokugo_source_code::{FileId, Sources};
+
+use crate::{Diagnostic, Importance, NoteKind, Severity};
+
+use super::{suggestion_message, Output, Renderer};
+
+/// Renders diagnostics using `annotate-snippets`, which lays them out the same way rustc does.
+pub struct AnnotateSnippets;
+
+impl Renderer for AnnotateSnippets {
+    fn render(&self, output: Output, sources: &Sources, diagnostics: Vec<Diagnostic>) -> Vec<u8> {
+        let renderer = match output {
+            Output::Plain => annotate_snippets::Renderer::plain(),
+            Output::Colored => annotate_snippets::Renderer::styled(),
+        };
+
+        let mut buffer = String::new();
+        for diagnostic in &diagnostics {
+            let suggestions = SuggestionMessages::new(diagnostic);
+            let message = message(sources, diagnostic, &suggestions);
+            buffer.push_str(&format!("{}\n\n", renderer.render(message)));
+        }
+        buffer.into_bytes()
+    }
+}
+
+fn level(severity: Severity) -> Level {
+    match severity {
+        // annotate-snippets does not have a separate level for compiler bugs.
+        Severity::Bug | Severity::Error => Level::Error,
+        Severity::Warning => Level::Warning,
+        Severity::Note => Level::Note,
+        Severity::Help => Level::Help,
+    }
+}
+
+/// Messages of a diagnostic's suggestions. annotate-snippets only borrows strings, so these have to
+/// be formatted up front.
+struct SuggestionMessages {
+    messages: Vec<String>,
+    children: Vec<SuggestionMessages>,
+}
+
+impl SuggestionMessages {
+    fn new(diagnostic: &Diagnostic) -> Self {
+        Self {
+            messages: diagnostic
+                .suggestions
+                .iter()
+                .map(suggestion_message)
+                .collect(),
+            children: diagnostic.children.iter().map(Self::new).collect(),
+        }
+    }
+}
+
+fn message<'a>(
+    sources: &'a Sources,
+    diagnostic: &'a Diagnostic,
+    suggestions: &'a SuggestionMessages,
+) -> Message<'a> {
+    let level = level(diagnostic.severity);
+
+    // annotate-snippets expects labels to be grouped into one snippet per file.
+    let mut files: Vec<FileId> = vec![];
+    let spans = diagnostic
+        .labels
+        .iter()
+        .map(|label| &label.source_span)
+        .chain(diagnostic.suggestions.iter().map(|s| &s.source_span));
+    for source_span in spans {
+        if !files.contains(&source_span.file_id) {
+            files.push(source_span.file_id);
+        }
+    }
+    let snippets = files.into_iter().map(|file_id| {
+        let file = sources.get(file_id);
+        Snippet::source(&file.source)
+            .origin(&file.filename)
+            .fold(true)
+            .annotations(
+                diagnostic
+                    .labels
+                    .iter()
+                    .filter(|label| label.source_span.file_id == file_id)
+                    .map(|label| {
+                        let annotation = match label.importance {
+                            Importance::Primary => level,
+                            Importance::Secondary => Level::Info,
+                        }
+                        .span(label.source_span.span.clone());
+                        if label.message.is_empty() {
+                            annotation
+                        } else {
+                            annotation.label(&label.message)
+                        }
+                    }),
+            )
+            .annotations(
+                diagnostic
+                    .suggestions
+                    .iter()
+                    .zip(&suggestions.messages)
+                    .filter(|(suggestion, _)| suggestion.source_span.file_id == file_id)
+                    .map(|(suggestion, message)| {
+                        Level::Help
+                            .span(suggestion.source_span.span.clone())
+                            .label(message)
+                    }),
+            )
+    });
+
+    let notes = diagnostic.notes.iter().map(|note| {
+        match note.kind {
+            NoteKind::Context => Level::Info,
+            NoteKind::Note => Level::Note,
+        }
+        .title(&note.message)
+    });
+    let children = diagnostic
+        .children
+        .iter()
+        .zip(&suggestions.children)
+        .map(|(child, suggestions)| message(sources, child, suggestions));
+
+    level
+        .title(&diagnostic.message)
+        .snippets(snippets)
+        .footers(notes)
+        .footers(children)
+}
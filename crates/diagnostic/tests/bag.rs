@@ -0,0 +1,69 @@
+use rokugo_diagnostic::{DiagnosticBag, Severity};
+
+#[test]
+fn has_errors() {
+    let mut bag = DiagnosticBag::new();
+    assert!(bag.is_empty());
+    bag.push(Severity::Warning.diagnostic("warning"));
+    assert!(!bag.is_empty());
+    assert!(!bag.has_errors());
+    bag.push(Severity::Bug.diagnostic("bug"));
+    assert!(bag.has_errors());
+}
+
+#[test]
+fn too_many_errors() {
+    let mut bag = DiagnosticBag::with_max_errors(2);
+    bag.extend([
+        Severity::Warning.diagnostic("warning 1"),
+        Severity::Error.diagnostic("error 1"),
+        Severity::Error.diagnostic("error 2"),
+        Severity::Warning.diagnostic("warning 2"),
+        Severity::Error.diagnostic("error 3"),
+        Severity::Warning.diagnostic("warning 3"),
+        Severity::Error.diagnostic("error 4"),
+    ]);
+
+    let messages: Vec<_> = bag.into_vec().into_iter().map(|d| d.message).collect();
+    assert_eq!(
+        messages,
+        [
+            "warning 1",
+            "error 1",
+            "error 2",
+            "warning 2",
+            "error 3",
+            "error 4",
+            "too many errors (more than 2); 1 other diagnostic not shown",
+        ]
+    );
+}
+
+#[test]
+fn within_limit() {
+    let mut bag = DiagnosticBag::with_max_errors(2);
+    bag.push(Severity::Error.diagnostic("error"));
+    bag.push(Severity::Warning.diagnostic("warning"));
+    assert_eq!(bag.into_vec().len(), 2);
+}
+
+#[test]
+fn too_many_errors_none_suppressed() {
+    let mut bag = DiagnosticBag::with_max_errors(2);
+    bag.extend([
+        Severity::Error.diagnostic("error 1"),
+        Severity::Error.diagnostic("error 2"),
+        Severity::Error.diagnostic("error 3"),
+    ]);
+
+    let messages: Vec<_> = bag.into_vec().into_iter().map(|d| d.message).collect();
+    assert_eq!(
+        messages,
+        [
+            "error 1",
+            "error 2",
+            "error 3",
+            "too many errors (more than 2)",
+        ]
+    );
+}
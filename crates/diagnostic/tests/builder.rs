@@ -0,0 +1,67 @@
+use rokugo_diagnostic::{note, Diagnostic, Importance, NoteKind, Severity};
+use rokugo_source_code::Sources;
+
+#[test]
+fn error_at() {
+    let (_, file_id) = Sources::with_one("test.rk", "let x = `\n");
+    let span = file_id.span(8..9);
+    assert_eq!(
+        Diagnostic::error_at(span.clone(), "unexpected ```", "not valid here"),
+        Severity::Error
+            .diagnostic("unexpected ```")
+            .with_label(Importance::Primary.label(span, "not valid here"))
+    );
+}
+
+#[test]
+fn is_fatal() {
+    assert!(Severity::Bug.is_fatal());
+    assert!(Severity::Error.is_fatal());
+    assert!(!Severity::Warning.is_fatal());
+    assert!(!Severity::Note.is_fatal());
+    assert!(!Severity::Help.is_fatal());
+
+    let warning = Severity::Warning.diagnostic("unused variable `x`");
+    assert!(!warning.is_fatal());
+    let nested = warning.with_child(
+        Severity::Note
+            .diagnostic("declared here")
+            .with_child(Severity::Error.diagnostic("`x` is also undefined")),
+    );
+    assert!(nested.is_fatal());
+}
+
+#[test]
+fn bulk_builders() {
+    let (_, file_id) = Sources::with_one("test.rk", "1 + 2 * 3\n");
+    let labels = vec![
+        Importance::Primary.label(file_id.span(2..3), "this operator"),
+        Importance::Primary.label(file_id.span(6..7), "and this operator"),
+    ];
+    let notes = vec![
+        note(
+            NoteKind::Note,
+            "operators `+` and `*` have no defined precedence",
+        ),
+        note(NoteKind::Context, "add parentheses to disambiguate"),
+    ];
+    let children = vec![Severity::Help.diagnostic("try `1 + (2 * 3)`")];
+
+    let bulk = Severity::Error
+        .diagnostic("ambiguous precedence")
+        .with_labels(labels.clone())
+        .with_notes(notes.clone())
+        .with_children(children.clone());
+    assert_eq!(bulk.labels, labels);
+    assert_eq!(bulk.notes, notes);
+    assert_eq!(bulk.children, children);
+
+    let chained = Severity::Error
+        .diagnostic("ambiguous precedence")
+        .with_label(labels[0].clone())
+        .with_label(labels[1].clone())
+        .with_note(notes[0].clone())
+        .with_note(notes[1].clone())
+        .with_child(children[0].clone());
+    assert_eq!(bulk, chained);
+}
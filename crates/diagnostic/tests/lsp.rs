@@ -0,0 +1,43 @@
+#![cfg(feature = "lsp")]
+
+use lsp_types::{DiagnosticSeverity, Position, Range};
+use rokugo_diagnostic::{note, Importance, NoteKind, Severity};
+use rokugo_source_code::Sources;
+
+#[test]
+fn to_lsp() {
+    let (sources, file_id) = Sources::with_one("test.rk", "let ä = 1\nlet y = `\n");
+    let diagnostic = Severity::Error
+        .diagnostic("unexpected ```")
+        .with_label(Importance::Primary.label(file_id.span(19..20), "invalid character"))
+        .with_label(Importance::Secondary.label(file_id.span(4..6), "declared here"))
+        .with_note(note(NoteKind::Note, "some note"))
+        .with_child(Severity::Help.diagnostic("remove it"));
+
+    let lsp = diagnostic.to_lsp(&sources);
+
+    assert_eq!(lsp.severity, Some(DiagnosticSeverity::ERROR));
+    assert_eq!(
+        lsp.range,
+        Range {
+            start: Position::new(1, 8),
+            end: Position::new(1, 9),
+        }
+    );
+    assert_eq!(
+        lsp.message,
+        "unexpected ```\nnote: some note\nhelp: remove it"
+    );
+
+    let related = lsp.related_information.unwrap();
+    assert_eq!(related.len(), 1);
+    assert_eq!(related[0].message, "declared here");
+    assert_eq!(
+        related[0].location.range,
+        Range {
+            start: Position::new(0, 4),
+            end: Position::new(0, 5),
+        }
+    );
+    assert!(related[0].location.uri.path().ends_with("/test.rk"));
+}
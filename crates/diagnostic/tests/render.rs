@@ -0,0 +1,198 @@
+use rokugo_diagnostic::{
+    note, render, render_json, render_with_options, sort_by_position, Applicability, Backend,
+    Importance, NoteKind, Output, RenderOptions, Severity, Suggestion,
+};
+use rokugo_source_code::{File, FileId, Sources};
+
+#[test]
+fn annotate_snippets() {
+    let (sources, file_id) = Sources::with_one("test.rk", "let x = `\n");
+    let diagnostic = Severity::Error
+        .diagnostic("unexpected ```")
+        .with_label(Importance::Primary.label(
+            file_id.span(8..9),
+            "this character is not valid in Rokugo source code",
+        ))
+        .with_note(note(NoteKind::Note, "some note"));
+
+    let rendered = render_with_options(
+        RenderOptions {
+            output: Output::Plain,
+            backend: Backend::AnnotateSnippets,
+            ..RenderOptions::default()
+        },
+        &sources,
+        vec![diagnostic],
+    );
+    let rendered = String::from_utf8(rendered).unwrap();
+
+    assert!(rendered.contains("error: unexpected ```"), "{rendered}");
+    assert!(rendered.contains("test.rk:1:9"), "{rendered}");
+    assert!(
+        rendered.contains("^ this character is not valid in Rokugo source code"),
+        "{rendered}"
+    );
+    assert!(rendered.contains("= note: some note"), "{rendered}");
+}
+
+#[test]
+fn group_by_file() {
+    let mut sources = Sources::default();
+    let b = sources.add(File {
+        filename: "b.rk".into(),
+        source: "let x = 1\nlet y = 2\n".into(),
+    });
+    let a = sources.add(File {
+        filename: "a.rk".into(),
+        source: "let z = 3\n".into(),
+    });
+    let diagnostics = vec![
+        Severity::Error
+            .diagnostic("second in b")
+            .with_label(Importance::Primary.label(b.span(14..15), "")),
+        Severity::Error.diagnostic("unattached"),
+        Severity::Error
+            .diagnostic("only in a")
+            .with_label(Importance::Primary.label(a.span(4..5), "")),
+        Severity::Error
+            .diagnostic("first in b")
+            .with_label(Importance::Primary.label(b.span(4..5), "")),
+    ];
+
+    let rendered = render_with_options(
+        RenderOptions {
+            group_by_file: true,
+            ..RenderOptions::default()
+        },
+        &sources,
+        diagnostics,
+    );
+    let rendered = String::from_utf8(rendered).unwrap();
+
+    let order = [
+        "── a.rk ──",
+        "only in a",
+        "── b.rk ──",
+        "first in b",
+        "second in b",
+        "── unattached ──",
+        "error: unattached",
+    ];
+    let positions: Vec<_> = order
+        .iter()
+        .map(|needle| {
+            rendered
+                .find(needle)
+                .unwrap_or_else(|| panic!("{needle:?} not found in {rendered}"))
+        })
+        .collect();
+    assert!(positions.is_sorted(), "{rendered}");
+}
+
+#[test]
+fn json() {
+    let (sources, file_id) = Sources::with_one("test.rk", "let x = 1\nlet y = `\n");
+    let diagnostic = Severity::Error
+        .diagnostic("unexpected ```")
+        .with_label(Importance::Primary.label(file_id.span(18..19), "invalid character"))
+        .with_note(note(NoteKind::Note, "some note"))
+        .with_child(
+            Severity::Help
+                .diagnostic("see here")
+                .with_label(Importance::Secondary.label(file_id.span(4..5), "")),
+        );
+
+    let rendered = render_json(&sources, vec![diagnostic]);
+
+    assert_eq!(
+        rendered,
+        concat!(
+            r#"[{"severity":"error","message":"unexpected ```","#,
+            r#""labels":[{"importance":"primary","file":"test.rk","start":18,"end":19,"#,
+            r#""line":2,"column":9,"end_line":2,"end_column":10,"message":"invalid character"}],"#,
+            r#""notes":[{"kind":"note","message":"some note"}],"#,
+            r#""children":[{"severity":"help","message":"see here","#,
+            r#""labels":[{"importance":"secondary","file":"test.rk","start":4,"end":5,"#,
+            r#""line":1,"column":5,"end_line":1,"end_column":6,"message":""}],"#,
+            r#""notes":[],"children":[],"suggestions":[]}],"suggestions":[]}]"#,
+        )
+    );
+}
+
+#[test]
+fn suggestion() {
+    let (sources, file_id) = Sources::with_one("test.rk", "let x = 1 + 2 * 3\n");
+    let diagnostic = Severity::Error
+        .diagnostic("ambiguous precedence")
+        .with_label(Importance::Primary.label(file_id.span(8..17), ""))
+        .with_suggestion(Suggestion {
+            source_span: file_id.span(8..13),
+            replacement: "(1 + 2)".into(),
+            applicability: Applicability::MaybeIncorrect,
+        });
+
+    let rendered =
+        String::from_utf8(render(Output::Plain, &sources, vec![diagnostic.clone()])).unwrap();
+    assert!(
+        rendered.contains("help: replace with `(1 + 2)`"),
+        "{rendered}"
+    );
+
+    let rendered = render_json(&sources, vec![diagnostic]);
+    assert!(
+        rendered.contains(concat!(
+            r#""suggestions":[{"file":"test.rk","start":8,"end":13,"#,
+            r#""line":1,"column":9,"end_line":1,"end_column":14,"#,
+            r#""replacement":"(1 + 2)","applicability":"maybe-incorrect"}]"#,
+        )),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn sorted_by_position() {
+    let mut sources = Sources::default();
+    let a = sources.add(File {
+        filename: "a.rk".into(),
+        source: "let x = 1\n".into(),
+    });
+    let b = sources.add(File {
+        filename: "b.rk".into(),
+        source: "let y = 2\n".into(),
+    });
+    let at = |file_id: FileId, start: usize, message: &str| {
+        Severity::Error
+            .diagnostic(message)
+            .with_label(Importance::Primary.label(file_id.span(start..start + 1), ""))
+    };
+    let mut diagnostics = vec![
+        Severity::Error.diagnostic("unattached"),
+        at(b, 0, "b0"),
+        at(a, 8, "a8 first"),
+        at(a, 4, "a4"),
+        at(a, 8, "a8 second"),
+    ];
+    sort_by_position(&mut diagnostics);
+
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        ["a4", "a8 first", "a8 second", "b0", "unattached"]
+    );
+}
+
+#[test]
+fn render_to_string() {
+    let (sources, file_id) = Sources::with_one("test.rk", "let x = `\n");
+    let diagnostic = Severity::Error
+        .diagnostic("unexpected ```")
+        .with_label(Importance::Primary.label(file_id.span(8..9), "not valid here"));
+
+    let rendered = diagnostic.render_to_string(&sources, Output::Plain);
+    assert!(rendered.contains("error: unexpected ```"), "{rendered}");
+    assert!(rendered.contains("let x = `"), "{rendered}");
+    assert!(rendered.contains("not valid here"), "{rendered}");
+
+    let displayed = Severity::Error.diagnostic("no labels").to_string();
+    assert!(displayed.contains("error: no labels"), "{displayed}");
+}
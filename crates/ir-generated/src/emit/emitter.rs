@@ -1,9 +1,16 @@
 use std::mem;
 
+use rokugo_backend_common::FunctionId;
 use rokugo_ir::{
     container::IrContainer,
     op_code::IrOpCode,
-    register::{chill::RegisterChill, natural::RegisterNat32, Register, RegisterId},
+    register::{
+        chill::RegisterChill,
+        float::{RegisterFloat32, RegisterFloat64},
+        integer::{RegisterInt16, RegisterInt32, RegisterInt64, RegisterInt8},
+        natural::{RegisterNat16, RegisterNat32, RegisterNat64, RegisterNat8},
+        Register, RegisterId,
+    },
 };
 
 use crate::errors::register::RegisterAllocationError;
@@ -26,15 +33,27 @@ impl IrEmitter {
 
 /// # Local Memory
 impl IrEmitter {
+    /// Allocates a virtual register, or prepare a native register to store a new 64-bit natural value.
+    pub fn alloc_register_nat64(
+        &mut self,
+        chill: RegisterChill,
+    ) -> Result<RegisterDropGuard<RegisterNat64>, RegisterAllocationError> {
+        let id = self.register_allocator.next_nat64()?;
+        self.emit_alloc_register(IrOpCode::AllocRegisterNat64, id, chill);
+
+        // SAFETY: This is safe, because this `id` is allocated with expected type by `RegisterAllocator`.
+        Ok(RegisterDropGuard::new(unsafe {
+            RegisterNat64::new_unchecked(id)
+        }))
+    }
+
     /// Allocates a virtual register, or prepare a native register to store a new 32-bit natural value.
     pub fn alloc_register_nat32(
         &mut self,
         chill: RegisterChill,
     ) -> Result<RegisterDropGuard<RegisterNat32>, RegisterAllocationError> {
         let id = self.register_allocator.next_nat32()?;
-        self.emit(IrOpCode::AllocRegisterNat32);
-        self.emit_register_id(id);
-        self.data.extend_from_slice(&chill.to_le_bytes());
+        self.emit_alloc_register(IrOpCode::AllocRegisterNat32, id, chill);
 
         // SAFETY: This is safe, because this `id` is allocated with expected type by `RegisterAllocator`.
         Ok(RegisterDropGuard::new(unsafe {
@@ -42,23 +61,272 @@ impl IrEmitter {
         }))
     }
 
+    /// Allocates a virtual register, or prepare a native register to store a new 16-bit natural value.
+    pub fn alloc_register_nat16(
+        &mut self,
+        chill: RegisterChill,
+    ) -> Result<RegisterDropGuard<RegisterNat16>, RegisterAllocationError> {
+        let id = self.register_allocator.next_nat16()?;
+        self.emit_alloc_register(IrOpCode::AllocRegisterNat16, id, chill);
+
+        // SAFETY: This is safe, because this `id` is allocated with expected type by `RegisterAllocator`.
+        Ok(RegisterDropGuard::new(unsafe {
+            RegisterNat16::new_unchecked(id)
+        }))
+    }
+
+    /// Allocates a virtual register, or prepare a native register to store a new 8-bit natural value.
+    pub fn alloc_register_nat8(
+        &mut self,
+        chill: RegisterChill,
+    ) -> Result<RegisterDropGuard<RegisterNat8>, RegisterAllocationError> {
+        let id = self.register_allocator.next_nat8()?;
+        self.emit_alloc_register(IrOpCode::AllocRegisterNat8, id, chill);
+
+        // SAFETY: This is safe, because this `id` is allocated with expected type by `RegisterAllocator`.
+        Ok(RegisterDropGuard::new(unsafe {
+            RegisterNat8::new_unchecked(id)
+        }))
+    }
+
+    /// Allocates a virtual register, or prepare a native register to store a new 64-bit integer value.
+    pub fn alloc_register_int64(
+        &mut self,
+        chill: RegisterChill,
+    ) -> Result<RegisterDropGuard<RegisterInt64>, RegisterAllocationError> {
+        let id = self.register_allocator.next_int64()?;
+        self.emit_alloc_register(IrOpCode::AllocRegisterInt64, id, chill);
+
+        // SAFETY: This is safe, because this `id` is allocated with expected type by `RegisterAllocator`.
+        Ok(RegisterDropGuard::new(unsafe {
+            RegisterInt64::new_unchecked(id)
+        }))
+    }
+
+    /// Allocates a virtual register, or prepare a native register to store a new 32-bit integer value.
+    pub fn alloc_register_int32(
+        &mut self,
+        chill: RegisterChill,
+    ) -> Result<RegisterDropGuard<RegisterInt32>, RegisterAllocationError> {
+        let id = self.register_allocator.next_int32()?;
+        self.emit_alloc_register(IrOpCode::AllocRegisterInt32, id, chill);
+
+        // SAFETY: This is safe, because this `id` is allocated with expected type by `RegisterAllocator`.
+        Ok(RegisterDropGuard::new(unsafe {
+            RegisterInt32::new_unchecked(id)
+        }))
+    }
+
+    /// Allocates a virtual register, or prepare a native register to store a new 16-bit integer value.
+    pub fn alloc_register_int16(
+        &mut self,
+        chill: RegisterChill,
+    ) -> Result<RegisterDropGuard<RegisterInt16>, RegisterAllocationError> {
+        let id = self.register_allocator.next_int16()?;
+        self.emit_alloc_register(IrOpCode::AllocRegisterInt16, id, chill);
+
+        // SAFETY: This is safe, because this `id` is allocated with expected type by `RegisterAllocator`.
+        Ok(RegisterDropGuard::new(unsafe {
+            RegisterInt16::new_unchecked(id)
+        }))
+    }
+
+    /// Allocates a virtual register, or prepare a native register to store a new 8-bit integer value.
+    pub fn alloc_register_int8(
+        &mut self,
+        chill: RegisterChill,
+    ) -> Result<RegisterDropGuard<RegisterInt8>, RegisterAllocationError> {
+        let id = self.register_allocator.next_int8()?;
+        self.emit_alloc_register(IrOpCode::AllocRegisterInt8, id, chill);
+
+        // SAFETY: This is safe, because this `id` is allocated with expected type by `RegisterAllocator`.
+        Ok(RegisterDropGuard::new(unsafe {
+            RegisterInt8::new_unchecked(id)
+        }))
+    }
+
+    /// Allocates a virtual register, or prepare a native register to store a new 64-bit floating point value.
+    pub fn alloc_register_float64(
+        &mut self,
+        chill: RegisterChill,
+    ) -> Result<RegisterDropGuard<RegisterFloat64>, RegisterAllocationError> {
+        let id = self.register_allocator.next_float64()?;
+        self.emit_alloc_register(IrOpCode::AllocRegisterFloat64, id, chill);
+
+        // SAFETY: This is safe, because this `id` is allocated with expected type by `RegisterAllocator`.
+        Ok(RegisterDropGuard::new(unsafe {
+            RegisterFloat64::new_unchecked(id)
+        }))
+    }
+
+    /// Allocates a virtual register, or prepare a native register to store a new 32-bit floating point value.
+    pub fn alloc_register_float32(
+        &mut self,
+        chill: RegisterChill,
+    ) -> Result<RegisterDropGuard<RegisterFloat32>, RegisterAllocationError> {
+        let id = self.register_allocator.next_float32()?;
+        self.emit_alloc_register(IrOpCode::AllocRegisterFloat32, id, chill);
+
+        // SAFETY: This is safe, because this `id` is allocated with expected type by `RegisterAllocator`.
+        Ok(RegisterDropGuard::new(unsafe {
+            RegisterFloat32::new_unchecked(id)
+        }))
+    }
+
     /// Drops a virtual register, what preverts it from being chilled.
     ///
     /// # Remarks
     ///
     /// This instruction is not dropping any memory like pointer etc. It is only a hint to the JIT compiler.
     pub fn drop_register(&mut self, register: RegisterDropGuard<impl Register>) -> &mut Self {
+        self.emit(IrOpCode::DropRegister);
+        self.emit_register_id(register.id());
         self.register_allocator.drop(register.id());
         #[cfg(debug_assertions)]
         mem::forget(register);
         self
     }
 
+    /// Loads 64-bit natural literal into register.
+    pub fn load_nat64(&mut self, register: &RegisterNat64, value: u64) -> &mut Self {
+        self.emit(IrOpCode::LoadNat64);
+        self.emit_register_id(register.id());
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
     /// Loads 32-bit natural literal into register.
     pub fn load_nat32(&mut self, register: &RegisterNat32, value: u32) -> &mut Self {
         self.emit(IrOpCode::LoadNat32);
         self.emit_register_id(register.id());
-        self.emit_nat32(value);
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Loads 16-bit natural literal into register.
+    pub fn load_nat16(&mut self, register: &RegisterNat16, value: u16) -> &mut Self {
+        self.emit(IrOpCode::LoadNat16);
+        self.emit_register_id(register.id());
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Loads 8-bit natural literal into register.
+    pub fn load_nat8(&mut self, register: &RegisterNat8, value: u8) -> &mut Self {
+        self.emit(IrOpCode::LoadNat8);
+        self.emit_register_id(register.id());
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Loads 64-bit integer literal into register.
+    pub fn load_int64(&mut self, register: &RegisterInt64, value: i64) -> &mut Self {
+        self.emit(IrOpCode::LoadInt64);
+        self.emit_register_id(register.id());
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Loads 32-bit integer literal into register.
+    pub fn load_int32(&mut self, register: &RegisterInt32, value: i32) -> &mut Self {
+        self.emit(IrOpCode::LoadInt32);
+        self.emit_register_id(register.id());
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Loads 16-bit integer literal into register.
+    pub fn load_int16(&mut self, register: &RegisterInt16, value: i16) -> &mut Self {
+        self.emit(IrOpCode::LoadInt16);
+        self.emit_register_id(register.id());
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Loads 8-bit integer literal into register.
+    pub fn load_int8(&mut self, register: &RegisterInt8, value: i8) -> &mut Self {
+        self.emit(IrOpCode::LoadInt8);
+        self.emit_register_id(register.id());
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Loads 64-bit floating point literal into register.
+    pub fn load_float64(&mut self, register: &RegisterFloat64, value: f64) -> &mut Self {
+        self.emit(IrOpCode::LoadFloat64);
+        self.emit_register_id(register.id());
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Loads 32-bit floating point literal into register.
+    pub fn load_float32(&mut self, register: &RegisterFloat32, value: f32) -> &mut Self {
+        self.emit(IrOpCode::LoadFloat32);
+        self.emit_register_id(register.id());
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+}
+
+/// # Arithmetic
+impl IrEmitter {
+    /// Adds `lhs` and `rhs` into `result`, wrapping around on overflow.
+    pub fn add_nat32(
+        &mut self,
+        result: &RegisterNat32,
+        lhs: &RegisterNat32,
+        rhs: &RegisterNat32,
+    ) -> &mut Self {
+        self.emit_binary(IrOpCode::AddNat32, result.id(), lhs.id(), rhs.id());
+        self
+    }
+
+    /// Subtracts `rhs` from `lhs` into `result`, wrapping around on overflow.
+    pub fn sub_nat32(
+        &mut self,
+        result: &RegisterNat32,
+        lhs: &RegisterNat32,
+        rhs: &RegisterNat32,
+    ) -> &mut Self {
+        self.emit_binary(IrOpCode::SubNat32, result.id(), lhs.id(), rhs.id());
+        self
+    }
+}
+
+/// # Control Flow
+impl IrEmitter {
+    /// Calls the function represented by `function_id`, passing it `arguments`, and stores the
+    /// returned value into `result`.
+    ///
+    /// # Panics
+    /// If more than 255 arguments are passed.
+    pub fn call(
+        &mut self,
+        result: &dyn Register,
+        function_id: FunctionId,
+        arguments: &[&dyn Register],
+    ) -> &mut Self {
+        let count = u8::try_from(arguments.len()).unwrap_or_else(|_| {
+            panic!(
+                "function calls may take at most 255 arguments, but {} were passed",
+                arguments.len()
+            )
+        });
+        self.emit(IrOpCode::Call);
+        self.emit_register_id(result.id());
+        self.data
+            .extend_from_slice(&bytemuck::cast::<FunctionId, u64>(function_id).to_le_bytes());
+        self.data.push(count);
+        for argument in arguments {
+            self.emit_register_id(argument.id());
+        }
+        self
+    }
+
+    /// Returns the value held by `register` from the current function.
+    pub fn return_value(&mut self, register: &dyn Register) -> &mut Self {
+        self.emit(IrOpCode::Return);
+        self.emit_register_id(register.id());
         self
     }
 }
@@ -73,8 +341,23 @@ impl IrEmitter {
         self.data.extend_from_slice(&register_id.to_le_bytes());
     }
 
-    fn emit_nat32(&mut self, value: u32) {
-        self.data.extend_from_slice(&value.to_le_bytes());
+    fn emit_binary(
+        &mut self,
+        op_code: IrOpCode,
+        result: RegisterId,
+        lhs: RegisterId,
+        rhs: RegisterId,
+    ) {
+        self.emit(op_code);
+        self.emit_register_id(result);
+        self.emit_register_id(lhs);
+        self.emit_register_id(rhs);
+    }
+
+    fn emit_alloc_register(&mut self, op_code: IrOpCode, id: RegisterId, chill: RegisterChill) {
+        self.emit(op_code);
+        self.emit_register_id(id);
+        self.data.extend_from_slice(&chill.to_le_bytes());
     }
 }
 
@@ -1,9 +1,6 @@
-use std::{
-    mem,
-    ops::{Deref, Range},
-};
+use std::{mem, ops::Deref};
 
-use rokugo_ir::register::{self, Register, RegisterId};
+use rokugo_ir::register::{self, Register, RegisterClass, RegisterId};
 
 use crate::errors::register::RegisterAllocationError;
 
@@ -40,19 +37,40 @@ where
     T: Register,
 {
     fn drop(&mut self) {
-        panic!("Register was dropped without DropRegister instruction")
+        // Panicking while already unwinding would abort the process, and hide the original panic.
+        if !std::thread::panicking() {
+            panic!("Register was dropped without DropRegister instruction")
+        }
     }
 }
 
 pub(crate) struct RegisterAllocator {
+    register_id_nat64: RegisterIdAllocator,
     register_id_nat32: RegisterIdAllocator,
+    register_id_nat16: RegisterIdAllocator,
+    register_id_nat8: RegisterIdAllocator,
+    register_id_int64: RegisterIdAllocator,
+    register_id_int32: RegisterIdAllocator,
+    register_id_int16: RegisterIdAllocator,
+    register_id_int8: RegisterIdAllocator,
+    register_id_float64: RegisterIdAllocator,
+    register_id_float32: RegisterIdAllocator,
     dropped_registers: Vec<RegisterId>,
 }
 
 impl RegisterAllocator {
     pub fn new() -> Self {
         Self {
+            register_id_nat64: RegisterIdAllocator::new(register::NAT64_ID_RANGE.start),
             register_id_nat32: RegisterIdAllocator::new(register::NAT32_ID_RANGE.start),
+            register_id_nat16: RegisterIdAllocator::new(register::NAT16_ID_RANGE.start),
+            register_id_nat8: RegisterIdAllocator::new(register::NAT8_ID_RANGE.start),
+            register_id_int64: RegisterIdAllocator::new(register::INT64_ID_RANGE.start),
+            register_id_int32: RegisterIdAllocator::new(register::INT32_ID_RANGE.start),
+            register_id_int16: RegisterIdAllocator::new(register::INT16_ID_RANGE.start),
+            register_id_int8: RegisterIdAllocator::new(register::INT8_ID_RANGE.start),
+            register_id_float64: RegisterIdAllocator::new(register::FLOAT64_ID_RANGE.start),
+            register_id_float32: RegisterIdAllocator::new(register::FLOAT32_ID_RANGE.start),
             dropped_registers: Vec::new(),
         }
     }
@@ -61,18 +79,81 @@ impl RegisterAllocator {
         self.dropped_registers.push(register_id)
     }
 
+    pub fn next_nat64(&mut self) -> Result<RegisterId, RegisterAllocationError> {
+        self.next(RegisterClass::Nat64)
+    }
+
     pub fn next_nat32(&mut self) -> Result<RegisterId, RegisterAllocationError> {
-        match self.get_dropped(register::NAT32_ID_RANGE) {
+        self.next(RegisterClass::Nat32)
+    }
+
+    pub fn next_nat16(&mut self) -> Result<RegisterId, RegisterAllocationError> {
+        self.next(RegisterClass::Nat16)
+    }
+
+    pub fn next_nat8(&mut self) -> Result<RegisterId, RegisterAllocationError> {
+        self.next(RegisterClass::Nat8)
+    }
+
+    pub fn next_int64(&mut self) -> Result<RegisterId, RegisterAllocationError> {
+        self.next(RegisterClass::Int64)
+    }
+
+    pub fn next_int32(&mut self) -> Result<RegisterId, RegisterAllocationError> {
+        self.next(RegisterClass::Int32)
+    }
+
+    pub fn next_int16(&mut self) -> Result<RegisterId, RegisterAllocationError> {
+        self.next(RegisterClass::Int16)
+    }
+
+    pub fn next_int8(&mut self) -> Result<RegisterId, RegisterAllocationError> {
+        self.next(RegisterClass::Int8)
+    }
+
+    pub fn next_float64(&mut self) -> Result<RegisterId, RegisterAllocationError> {
+        self.next(RegisterClass::Float64)
+    }
+
+    pub fn next_float32(&mut self) -> Result<RegisterId, RegisterAllocationError> {
+        self.next(RegisterClass::Float32)
+    }
+
+    fn next(&mut self, class: RegisterClass) -> Result<RegisterId, RegisterAllocationError> {
+        match self.get_dropped(class) {
             Some(r) => Ok(r),
-            None => self.register_id_nat32.next(register::NAT32_ID_RANGE.end),
+            None => self.id_allocator(class).next(class.range().end),
+        }
+    }
+
+    fn id_allocator(&mut self, class: RegisterClass) -> &mut RegisterIdAllocator {
+        match class {
+            RegisterClass::Nat64 => &mut self.register_id_nat64,
+            RegisterClass::Nat32 => &mut self.register_id_nat32,
+            RegisterClass::Nat16 => &mut self.register_id_nat16,
+            RegisterClass::Nat8 => &mut self.register_id_nat8,
+            RegisterClass::Int64 => &mut self.register_id_int64,
+            RegisterClass::Int32 => &mut self.register_id_int32,
+            RegisterClass::Int16 => &mut self.register_id_int16,
+            RegisterClass::Int8 => &mut self.register_id_int8,
+            RegisterClass::Float64 => &mut self.register_id_float64,
+            RegisterClass::Float32 => &mut self.register_id_float32,
+            RegisterClass::Float16
+            | RegisterClass::Special
+            | RegisterClass::Ptr
+            | RegisterClass::Bit => {
+                // Only the `next_*` methods call into this, and there is none for these classes.
+                unreachable!("{class:?} registers are never allocated by the register allocator")
+            }
         }
     }
 
-    fn get_dropped(&mut self, range: Range<u16>) -> Option<RegisterId> {
-        if let Some(index) = self.dropped_registers.iter().position(|x| {
-            let unwrapped = x.into_inner();
-            unwrapped >= range.start && unwrapped < range.end
-        }) {
+    fn get_dropped(&mut self, class: RegisterClass) -> Option<RegisterId> {
+        if let Some(index) = self
+            .dropped_registers
+            .iter()
+            .position(|x| x.class() == class)
+        {
             Some(self.dropped_registers.remove(index))
         } else {
             None
@@ -101,6 +182,6 @@ impl RegisterIdAllocator {
         self.next_register_id += 1;
 
         // SAFETY: This is safe, because `RegisterId` is a wrapper around `u16`.
-        Ok(unsafe { mem::transmute(id) })
+        Ok(unsafe { mem::transmute::<u16, RegisterId>(id) })
     }
 }
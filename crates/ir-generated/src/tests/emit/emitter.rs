@@ -1,3 +1,4 @@
+use rokugo_backend_common::FunctionId;
 use rokugo_ir::{
     container::IrContainer,
     op_code::IrInstruction,
@@ -10,13 +11,9 @@ fn emit_and_assert<const LENGTH: usize>(f: fn(&mut IrEmitter) -> [IrInstruction<
     let mut ir = IrEmitter::new();
     let data = f(&mut ir);
 
-    let mut i = 0;
-    for instruction in IrContainer::from(ir).iter() {
-        assert_eq!(data[i], instruction.unwrap());
-        i += 1;
-    }
-
-    assert_eq!(data.len() - 1, i);
+    let ir = IrContainer::from(ir);
+    let instructions: Vec<_> = ir.iter().map(|instruction| instruction.unwrap()).collect();
+    assert_eq!(instructions, data);
 }
 
 #[test]
@@ -34,3 +31,215 @@ fn load_nat32() {
         ]
     });
 }
+
+#[test]
+fn load_nat64() {
+    emit_and_assert(|ir| {
+        let chill = RegisterChill::default();
+        let register = ir.alloc_register_nat64(chill.clone()).unwrap();
+        let register_id = register.id();
+        ir.load_nat64(&register, u64::MAX).drop_register(register);
+
+        [
+            IrInstruction::AllocRegisterNat64(register_id, chill),
+            IrInstruction::LoadNat64(register_id, u64::MAX),
+            IrInstruction::DropRegister(register_id),
+        ]
+    });
+}
+
+#[test]
+fn load_nat16() {
+    emit_and_assert(|ir| {
+        let chill = RegisterChill::default();
+        let register = ir.alloc_register_nat16(chill.clone()).unwrap();
+        let register_id = register.id();
+        ir.load_nat16(&register, u16::MAX).drop_register(register);
+
+        [
+            IrInstruction::AllocRegisterNat16(register_id, chill),
+            IrInstruction::LoadNat16(register_id, u16::MAX),
+            IrInstruction::DropRegister(register_id),
+        ]
+    });
+}
+
+#[test]
+fn load_nat8() {
+    emit_and_assert(|ir| {
+        let chill = RegisterChill::default();
+        let register = ir.alloc_register_nat8(chill.clone()).unwrap();
+        let register_id = register.id();
+        ir.load_nat8(&register, 8).drop_register(register);
+
+        [
+            IrInstruction::AllocRegisterNat8(register_id, chill),
+            IrInstruction::LoadNat8(register_id, 8),
+            IrInstruction::DropRegister(register_id),
+        ]
+    });
+}
+
+#[test]
+fn load_int64() {
+    emit_and_assert(|ir| {
+        let chill = RegisterChill::default();
+        let register = ir.alloc_register_int64(chill.clone()).unwrap();
+        let register_id = register.id();
+        ir.load_int64(&register, i64::MIN).drop_register(register);
+
+        [
+            IrInstruction::AllocRegisterInt64(register_id, chill),
+            IrInstruction::LoadInt64(register_id, i64::MIN),
+            IrInstruction::DropRegister(register_id),
+        ]
+    });
+}
+
+#[test]
+fn load_int32() {
+    emit_and_assert(|ir| {
+        let chill = RegisterChill::default();
+        let register = ir.alloc_register_int32(chill.clone()).unwrap();
+        let register_id = register.id();
+        ir.load_int32(&register, -65).drop_register(register);
+
+        [
+            IrInstruction::AllocRegisterInt32(register_id, chill),
+            IrInstruction::LoadInt32(register_id, -65),
+            IrInstruction::DropRegister(register_id),
+        ]
+    });
+}
+
+#[test]
+fn load_int16() {
+    emit_and_assert(|ir| {
+        let chill = RegisterChill::default();
+        let register = ir.alloc_register_int16(chill.clone()).unwrap();
+        let register_id = register.id();
+        ir.load_int16(&register, -16).drop_register(register);
+
+        [
+            IrInstruction::AllocRegisterInt16(register_id, chill),
+            IrInstruction::LoadInt16(register_id, -16),
+            IrInstruction::DropRegister(register_id),
+        ]
+    });
+}
+
+#[test]
+fn load_int8() {
+    emit_and_assert(|ir| {
+        let chill = RegisterChill::default();
+        let register = ir.alloc_register_int8(chill.clone()).unwrap();
+        let register_id = register.id();
+        ir.load_int8(&register, i8::MIN).drop_register(register);
+
+        [
+            IrInstruction::AllocRegisterInt8(register_id, chill),
+            IrInstruction::LoadInt8(register_id, i8::MIN),
+            IrInstruction::DropRegister(register_id),
+        ]
+    });
+}
+
+#[test]
+fn load_float64() {
+    emit_and_assert(|ir| {
+        let chill = RegisterChill::default();
+        let register = ir.alloc_register_float64(chill.clone()).unwrap();
+        let register_id = register.id();
+        ir.load_float64(&register, -1.5).drop_register(register);
+
+        [
+            IrInstruction::AllocRegisterFloat64(register_id, chill),
+            IrInstruction::LoadFloat64(register_id, -1.5),
+            IrInstruction::DropRegister(register_id),
+        ]
+    });
+}
+
+#[test]
+fn load_float32() {
+    emit_and_assert(|ir| {
+        let chill = RegisterChill::default();
+        let register = ir.alloc_register_float32(chill.clone()).unwrap();
+        let register_id = register.id();
+        ir.load_float32(&register, 0.25).drop_register(register);
+
+        [
+            IrInstruction::AllocRegisterFloat32(register_id, chill),
+            IrInstruction::LoadFloat32(register_id, 0.25),
+            IrInstruction::DropRegister(register_id),
+        ]
+    });
+}
+
+#[test]
+fn add_nat32() {
+    emit_and_assert(|ir| {
+        let chill = RegisterChill::default();
+        let lhs = ir.alloc_register_nat32(chill.clone()).unwrap();
+        let rhs = ir.alloc_register_nat32(chill.clone()).unwrap();
+        let (lhs_id, rhs_id) = (lhs.id(), rhs.id());
+        ir.load_nat32(&lhs, 1)
+            .load_nat32(&rhs, 2)
+            .add_nat32(&lhs, &lhs, &rhs)
+            .sub_nat32(&rhs, &lhs, &rhs)
+            .drop_register(lhs)
+            .drop_register(rhs);
+
+        [
+            IrInstruction::AllocRegisterNat32(lhs_id, chill.clone()),
+            IrInstruction::AllocRegisterNat32(rhs_id, chill),
+            IrInstruction::LoadNat32(lhs_id, 1),
+            IrInstruction::LoadNat32(rhs_id, 2),
+            IrInstruction::AddNat32(lhs_id, lhs_id, rhs_id),
+            IrInstruction::SubNat32(rhs_id, lhs_id, rhs_id),
+            IrInstruction::DropRegister(lhs_id),
+            IrInstruction::DropRegister(rhs_id),
+        ]
+    });
+}
+
+#[test]
+fn call() {
+    let mut ir = IrEmitter::new();
+    let nat = ir.alloc_register_nat32(RegisterChill::default()).unwrap();
+    let int = ir.alloc_register_int8(RegisterChill::default()).unwrap();
+    let function_id = bytemuck::cast::<u64, FunctionId>(3);
+    ir.call(&*nat, function_id, &[])
+        .call(&*int, function_id, &[&*nat, &*int, &*nat]);
+    let (nat_id, int_id) = (nat.id(), int.id());
+    ir.drop_register(nat).drop_register(int);
+
+    let ir = IrContainer::from(ir);
+    let calls: Vec<_> = ir
+        .iter()
+        .skip(2)
+        .take(2)
+        .map(|instruction| match instruction.unwrap() {
+            IrInstruction::Call(result, function_id, arguments) => {
+                (result, function_id, arguments.iter().collect::<Vec<_>>())
+            }
+            instruction => panic!("expected a call, found {instruction:?}"),
+        })
+        .collect();
+    assert_eq!(
+        calls,
+        [
+            (nat_id, function_id, vec![]),
+            (int_id, function_id, vec![nat_id, int_id, nat_id]),
+        ]
+    );
+}
+
+#[test]
+#[should_panic = "function calls may take at most 255 arguments, but 256 were passed"]
+fn call_too_many_arguments() {
+    let mut ir = IrEmitter::new();
+    let nat = ir.alloc_register_nat32(RegisterChill::default()).unwrap();
+    let argument: &dyn Register = &*nat;
+    ir.call(argument, bytemuck::cast(3u64), &[argument; 256]);
+}
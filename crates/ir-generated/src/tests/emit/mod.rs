@@ -1,2 +1,3 @@
 pub mod emitter;
+pub mod op_code_display;
 pub mod register_allocator;
@@ -0,0 +1,48 @@
+use rokugo_common::color::ColoredDisplay;
+use rokugo_ir::{container::IrContainer, register::chill::RegisterChill};
+use termcolor::NoColor;
+
+use crate::emit::emitter::IrEmitter;
+
+fn display(ir: IrContainer) -> String {
+    let mut output = NoColor::new(vec![]);
+    ir.fmt_with_color(&mut output).unwrap();
+    String::from_utf8(output.into_inner()).unwrap()
+}
+
+#[test]
+fn display_instructions() {
+    let mut ir = IrEmitter::new();
+    let nat = ir.alloc_register_nat32(RegisterChill::default()).unwrap();
+    let int = ir.alloc_register_int8(RegisterChill::default()).unwrap();
+    ir.load_nat32(&nat, 42).load_int8(&int, -1);
+    ir.drop_register(nat).drop_register(int);
+
+    assert_eq!(
+        display(ir.into()),
+        "AllocRegisterNat32 %r1024, chill=[]\n\
+         AllocRegisterInt8 %r7168, chill=[]\n\
+         LoadNat32 %r1024, 42\n\
+         LoadInt8 %r7168, -1\n\
+         DropRegister %r1024\n\
+         DropRegister %r7168\n"
+    );
+}
+
+#[test]
+fn display_call() {
+    let mut ir = IrEmitter::new();
+    let nat = ir.alloc_register_nat32(RegisterChill::default()).unwrap();
+    let int = ir.alloc_register_int8(RegisterChill::default()).unwrap();
+    ir.call(&*int, bytemuck::cast(3u64), &[&*nat, &*int]);
+    ir.drop_register(nat).drop_register(int);
+
+    assert_eq!(
+        display(ir.into()),
+        "AllocRegisterNat32 %r1024, chill=[]\n\
+         AllocRegisterInt8 %r7168, chill=[]\n\
+         Call %r7168, $3, %r1024, %r7168\n\
+         DropRegister %r1024\n\
+         DropRegister %r7168\n"
+    );
+}
@@ -1,13 +1,84 @@
-use crate::emit::register_allocator::RegisterAllocator;
+use rokugo_ir::register::{chill::RegisterChill, RegisterClass, RegisterId};
 
-#[test]
-fn alloc_overflow() {
+use crate::{
+    emit::{emitter::IrEmitter, register_allocator::RegisterAllocator},
+    errors::register::RegisterAllocationError,
+};
+
+fn assert_overflows(
+    next: fn(&mut RegisterAllocator) -> Result<RegisterId, RegisterAllocationError>,
+    class: RegisterClass,
+) {
     let mut allocator = RegisterAllocator::new();
-    for _ in 0..u16::MAX {
-        if allocator.next_nat32().is_err() {
-            return;
-        }
+    for _ in class.range() {
+        let id = next(&mut allocator).unwrap();
+        assert_eq!(id.class(), class);
     }
+    assert!(next(&mut allocator).is_err());
+}
+
+#[test]
+fn alloc_overflow_nat64() {
+    assert_overflows(RegisterAllocator::next_nat64, RegisterClass::Nat64);
+}
 
-    panic!("Should overflow");
+#[test]
+fn alloc_overflow_nat32() {
+    assert_overflows(RegisterAllocator::next_nat32, RegisterClass::Nat32);
+}
+
+#[test]
+fn alloc_overflow_nat16() {
+    assert_overflows(RegisterAllocator::next_nat16, RegisterClass::Nat16);
+}
+
+#[test]
+fn alloc_overflow_nat8() {
+    assert_overflows(RegisterAllocator::next_nat8, RegisterClass::Nat8);
+}
+
+#[test]
+fn alloc_overflow_int64() {
+    assert_overflows(RegisterAllocator::next_int64, RegisterClass::Int64);
+}
+
+#[test]
+fn alloc_overflow_int32() {
+    assert_overflows(RegisterAllocator::next_int32, RegisterClass::Int32);
+}
+
+#[test]
+fn alloc_overflow_int16() {
+    assert_overflows(RegisterAllocator::next_int16, RegisterClass::Int16);
+}
+
+#[test]
+fn alloc_overflow_int8() {
+    assert_overflows(RegisterAllocator::next_int8, RegisterClass::Int8);
+}
+
+#[test]
+fn alloc_overflow_float64() {
+    assert_overflows(RegisterAllocator::next_float64, RegisterClass::Float64);
+}
+
+#[test]
+fn alloc_overflow_float32() {
+    assert_overflows(RegisterAllocator::next_float32, RegisterClass::Float32);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "Register was dropped without DropRegister instruction"]
+fn guard_dropped_without_instruction() {
+    let mut ir = IrEmitter::new();
+    let _register = ir.alloc_register_nat32(RegisterChill::default()).unwrap();
+}
+
+#[test]
+#[should_panic = "unrelated failure"]
+fn guard_dropped_while_panicking() {
+    let mut ir = IrEmitter::new();
+    let _register = ir.alloc_register_nat32(RegisterChill::default()).unwrap();
+    panic!("unrelated failure");
 }
@@ -1,8 +1,11 @@
 use std::mem;
 
+use rokugo_backend_common::FunctionId;
+use rokugo_common::color::ColoredDisplay;
+
 use crate::{
     instruction_read_error::IrInstructionReadError,
-    op_code::{IrInstruction, IrOpCode},
+    op_code::{IrInstruction, IrOpCode, RegisterIds},
     register::{chill::RegisterChill, RegisterId},
 };
 
@@ -18,7 +21,7 @@ impl IrContainer {
         IrContainer { data }
     }
 
-    pub fn iter(&self) -> IrContainerIterator {
+    pub fn iter(&self) -> IrContainerIterator<'_> {
         IrContainerIterator {
             container: self,
             index: 0,
@@ -41,10 +44,10 @@ pub struct IrContainerIterator<'c> {
 }
 
 impl<'c> IrContainerIterator<'c> {
-    /// # Safety
-    /// Caller must ensure that the data is valid IR in valid version.
-    unsafe fn read_instruction(&mut self) -> Result<IrInstruction<'c>, IrInstructionReadError> {
-        let op_code = match IrOpCode::try_from(self.read_nat16()) {
+    /// Reads the next instruction. Invalid op codes and operands cut off by the end of the container are reported
+    /// as errors.
+    fn read_instruction(&mut self) -> Result<IrInstruction<'c>, IrInstructionReadError> {
+        let op_code = match IrOpCode::try_from(self.read_nat16()?) {
             Ok(op_code) => op_code,
             Err(err) => {
                 return Err(IrInstructionReadError::InvalidOpCode(
@@ -55,45 +58,148 @@ impl<'c> IrContainerIterator<'c> {
         };
         match op_code {
             // ! Local Memory
-            IrOpCode::AllocRegisterNat32 => {
-                const REGISTER_CHILL_SIZE: usize = mem::size_of::<RegisterChill>();
-                Ok(IrInstruction::AllocRegisterNat32(
-                    self.read_register_id(),
-                    RegisterChill::from_le_bytes(&self.read_byte_array::<REGISTER_CHILL_SIZE>()),
-                ))
-            }
-            IrOpCode::DropRegister => Ok(IrInstruction::DropRegister(self.read_register_id())),
+            IrOpCode::AllocRegisterNat64 => Ok(IrInstruction::AllocRegisterNat64(
+                self.read_register_id()?,
+                self.read_register_chill()?,
+            )),
+            IrOpCode::AllocRegisterNat32 => Ok(IrInstruction::AllocRegisterNat32(
+                self.read_register_id()?,
+                self.read_register_chill()?,
+            )),
+            IrOpCode::AllocRegisterNat16 => Ok(IrInstruction::AllocRegisterNat16(
+                self.read_register_id()?,
+                self.read_register_chill()?,
+            )),
+            IrOpCode::AllocRegisterNat8 => Ok(IrInstruction::AllocRegisterNat8(
+                self.read_register_id()?,
+                self.read_register_chill()?,
+            )),
+            IrOpCode::AllocRegisterInt64 => Ok(IrInstruction::AllocRegisterInt64(
+                self.read_register_id()?,
+                self.read_register_chill()?,
+            )),
+            IrOpCode::AllocRegisterInt32 => Ok(IrInstruction::AllocRegisterInt32(
+                self.read_register_id()?,
+                self.read_register_chill()?,
+            )),
+            IrOpCode::AllocRegisterInt16 => Ok(IrInstruction::AllocRegisterInt16(
+                self.read_register_id()?,
+                self.read_register_chill()?,
+            )),
+            IrOpCode::AllocRegisterInt8 => Ok(IrInstruction::AllocRegisterInt8(
+                self.read_register_id()?,
+                self.read_register_chill()?,
+            )),
+            IrOpCode::AllocRegisterFloat64 => Ok(IrInstruction::AllocRegisterFloat64(
+                self.read_register_id()?,
+                self.read_register_chill()?,
+            )),
+            IrOpCode::AllocRegisterFloat32 => Ok(IrInstruction::AllocRegisterFloat32(
+                self.read_register_id()?,
+                self.read_register_chill()?,
+            )),
+            IrOpCode::DropRegister => Ok(IrInstruction::DropRegister(self.read_register_id()?)),
+            IrOpCode::LoadNat64 => Ok(IrInstruction::LoadNat64(
+                self.read_register_id()?,
+                u64::from_le_bytes(self.read_byte_array()?),
+            )),
             IrOpCode::LoadNat32 => Ok(IrInstruction::LoadNat32(
-                self.read_register_id(),
-                self.read_nat32(),
+                self.read_register_id()?,
+                u32::from_le_bytes(self.read_byte_array()?),
+            )),
+            IrOpCode::LoadNat16 => Ok(IrInstruction::LoadNat16(
+                self.read_register_id()?,
+                u16::from_le_bytes(self.read_byte_array()?),
+            )),
+            IrOpCode::LoadNat8 => Ok(IrInstruction::LoadNat8(
+                self.read_register_id()?,
+                u8::from_le_bytes(self.read_byte_array()?),
+            )),
+            IrOpCode::LoadInt64 => Ok(IrInstruction::LoadInt64(
+                self.read_register_id()?,
+                i64::from_le_bytes(self.read_byte_array()?),
+            )),
+            IrOpCode::LoadInt32 => Ok(IrInstruction::LoadInt32(
+                self.read_register_id()?,
+                i32::from_le_bytes(self.read_byte_array()?),
+            )),
+            IrOpCode::LoadInt16 => Ok(IrInstruction::LoadInt16(
+                self.read_register_id()?,
+                i16::from_le_bytes(self.read_byte_array()?),
             )),
+            IrOpCode::LoadInt8 => Ok(IrInstruction::LoadInt8(
+                self.read_register_id()?,
+                i8::from_le_bytes(self.read_byte_array()?),
+            )),
+            IrOpCode::LoadFloat64 => Ok(IrInstruction::LoadFloat64(
+                self.read_register_id()?,
+                f64::from_le_bytes(self.read_byte_array()?),
+            )),
+            IrOpCode::LoadFloat32 => Ok(IrInstruction::LoadFloat32(
+                self.read_register_id()?,
+                f32::from_le_bytes(self.read_byte_array()?),
+            )),
+            // ! Arithmetic
+            IrOpCode::AddNat32 => Ok(IrInstruction::AddNat32(
+                self.read_register_id()?,
+                self.read_register_id()?,
+                self.read_register_id()?,
+            )),
+            IrOpCode::SubNat32 => Ok(IrInstruction::SubNat32(
+                self.read_register_id()?,
+                self.read_register_id()?,
+                self.read_register_id()?,
+            )),
+            // ! Control Flow
+            IrOpCode::Call => {
+                let result = self.read_register_id()?;
+                let function_id = self.read_function_id()?;
+                let arguments_count = self.read_slice(1)?[0];
+                let arguments =
+                    self.read_slice(usize::from(arguments_count) * mem::size_of::<RegisterId>())?;
+                Ok(IrInstruction::Call(
+                    result,
+                    function_id,
+                    RegisterIds::from_bytes(arguments),
+                ))
+            }
+            IrOpCode::Return => Ok(IrInstruction::Return(self.read_register_id()?)),
         }
     }
 
-    fn read_byte_array<const LENGTH: usize>(&mut self) -> [u8; LENGTH] {
-        let array = self.container.data[self.index..self.index + LENGTH]
-            .try_into()
-            .unwrap();
-        self.index += LENGTH;
-        array
+    fn read_slice(&mut self, length: usize) -> Result<&'c [u8], IrInstructionReadError> {
+        let slice = self
+            .container
+            .data
+            .get(self.index..self.index + length)
+            .ok_or(IrInstructionReadError::UnexpectedEof { at: self.index })?;
+        self.index += length;
+        Ok(slice)
     }
 
-    /// # Safety
-    /// Caller must ensure that the next bytes in data is a valid [`RegisterId`].
-    unsafe fn read_register_id(&mut self) -> RegisterId {
-        mem::transmute(self.read_nat16())
+    fn read_byte_array<const LENGTH: usize>(
+        &mut self,
+    ) -> Result<[u8; LENGTH], IrInstructionReadError> {
+        Ok(self.read_slice(LENGTH)?.try_into().unwrap())
     }
 
-    /// # Safety
-    /// Caller must ensure that the next bytes in data is a valid [`u32`].
-    unsafe fn read_nat32(&mut self) -> u32 {
-        u32::from_le_bytes(self.read_byte_array())
+    fn read_register_chill(&mut self) -> Result<RegisterChill, IrInstructionReadError> {
+        const REGISTER_CHILL_SIZE: usize = mem::size_of::<RegisterChill>();
+        Ok(RegisterChill::from_le_bytes(
+            &self.read_byte_array::<REGISTER_CHILL_SIZE>()?,
+        ))
     }
 
-    /// # Safety
-    /// Caller must ensure that the next bytes in data is a valid [`u16`].
-    unsafe fn read_nat16(&mut self) -> u16 {
-        u16::from_le_bytes(self.read_byte_array())
+    fn read_register_id(&mut self) -> Result<RegisterId, IrInstructionReadError> {
+        Ok(RegisterId(self.read_nat16()?))
+    }
+
+    fn read_function_id(&mut self) -> Result<FunctionId, IrInstructionReadError> {
+        Ok(bytemuck::cast(u64::from_le_bytes(self.read_byte_array()?)))
+    }
+
+    fn read_nat16(&mut self) -> Result<u16, IrInstructionReadError> {
+        Ok(u16::from_le_bytes(self.read_byte_array()?))
     }
 }
 
@@ -102,11 +208,32 @@ impl<'c> Iterator for IrContainerIterator<'c> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.index < self.container.data.len() {
-            // SAFETY: `IrContainerIterator` can be created only from `IrContainer` which forces ensuring data to be
-            // valid IR in current version.
-            Some(unsafe { self.read_instruction() })
+            let result = self.read_instruction();
+            if result.is_err() {
+                // The position of the next instruction is unknown, so nothing after the error can be read.
+                self.index = self.container.data.len();
+            }
+            Some(result)
         } else {
             None
         }
     }
 }
+
+impl ColoredDisplay for IrContainer {
+    /// Displays every instruction on its own line. If an instruction cannot be decoded, the error is displayed in
+    /// its place, and nothing after it is displayed, since the position of the next instruction is unknown.
+    fn fmt_with_color(&self, f: &mut dyn termcolor::WriteColor) -> std::io::Result<()> {
+        for instruction in self.iter() {
+            match instruction {
+                Ok(instruction) => instruction.fmt_with_color(f)?,
+                Err(error) => {
+                    error.fmt_with_color(f)?;
+                    break;
+                }
+            }
+        }
+        f.reset()?;
+        Ok(())
+    }
+}
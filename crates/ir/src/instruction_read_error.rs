@@ -3,6 +3,10 @@ use std::{error::Error, fmt};
 #[derive(Debug)]
 pub enum IrInstructionReadError {
     InvalidOpCode(usize, u16),
+    /// The container ends in the middle of an instruction, while reading the operand starting at `at`.
+    UnexpectedEof {
+        at: usize,
+    },
 }
 
 impl Error for IrInstructionReadError {}
@@ -13,6 +17,9 @@ impl fmt::Display for IrInstructionReadError {
             IrInstructionReadError::InvalidOpCode(index, op_code) => {
                 write!(f, "Invalid op code `{}` at index: {}", op_code, index)
             }
+            IrInstructionReadError::UnexpectedEof { at } => {
+                write!(f, "Unexpected end of IR at index: {}", at)
+            }
         }
     }
 }
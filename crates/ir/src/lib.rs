@@ -3,3 +3,6 @@ pub mod instruction_read_error;
 pub mod op_code;
 pub mod op_code_display;
 pub mod register;
+
+#[cfg(test)]
+mod tests;
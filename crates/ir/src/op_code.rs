@@ -1,4 +1,7 @@
+use std::{fmt, mem};
+
 use num_enum::TryFromPrimitive;
+use rokugo_backend_common::FunctionId;
 
 use crate::register::{chill::RegisterChill, RegisterId};
 
@@ -6,6 +9,14 @@ use crate::register::{chill::RegisterChill, RegisterId};
 #[repr(u16)]
 pub enum IrOpCode {
     // ! Local Memory
+    /// Allocates a virtual register, or prepare a native register to store a new 64-bit natural value.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - defined register
+    /// - [`RegisterChill`] - most optimal registers to [chill][crate::register::chill]
+    AllocRegisterNat64,
+
     /// Allocates a virtual register, or prepare a native register to store a new 32-bit natural value.
     ///
     /// # Layout
@@ -14,6 +25,70 @@ pub enum IrOpCode {
     /// - [`RegisterChill`] - most optimal registers to [chill][crate::register::chill]
     AllocRegisterNat32,
 
+    /// Allocates a virtual register, or prepare a native register to store a new 16-bit natural value.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - defined register
+    /// - [`RegisterChill`] - most optimal registers to [chill][crate::register::chill]
+    AllocRegisterNat16,
+
+    /// Allocates a virtual register, or prepare a native register to store a new 8-bit natural value.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - defined register
+    /// - [`RegisterChill`] - most optimal registers to [chill][crate::register::chill]
+    AllocRegisterNat8,
+
+    /// Allocates a virtual register, or prepare a native register to store a new 64-bit integer value.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - defined register
+    /// - [`RegisterChill`] - most optimal registers to [chill][crate::register::chill]
+    AllocRegisterInt64,
+
+    /// Allocates a virtual register, or prepare a native register to store a new 32-bit integer value.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - defined register
+    /// - [`RegisterChill`] - most optimal registers to [chill][crate::register::chill]
+    AllocRegisterInt32,
+
+    /// Allocates a virtual register, or prepare a native register to store a new 16-bit integer value.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - defined register
+    /// - [`RegisterChill`] - most optimal registers to [chill][crate::register::chill]
+    AllocRegisterInt16,
+
+    /// Allocates a virtual register, or prepare a native register to store a new 8-bit integer value.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - defined register
+    /// - [`RegisterChill`] - most optimal registers to [chill][crate::register::chill]
+    AllocRegisterInt8,
+
+    /// Allocates a virtual register, or prepare a native register to store a new 64-bit floating point value.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - defined register
+    /// - [`RegisterChill`] - most optimal registers to [chill][crate::register::chill]
+    AllocRegisterFloat64,
+
+    /// Allocates a virtual register, or prepare a native register to store a new 32-bit floating point value.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - defined register
+    /// - [`RegisterChill`] - most optimal registers to [chill][crate::register::chill]
+    AllocRegisterFloat32,
+
     /// Drops a virtual register, what preverts it from being chilled.
     ///
     /// # Remarks
@@ -25,6 +100,14 @@ pub enum IrOpCode {
     /// - [`RegisterId`] - register to drop
     DropRegister,
 
+    /// Loads 64-bit natural literal into register.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - destination register
+    /// - [`u64`] - literal value
+    LoadNat64,
+
     /// Loads 32-bit natural literal into register.
     ///
     /// # Layout
@@ -32,14 +115,172 @@ pub enum IrOpCode {
     /// - [`RegisterId`] - destination register
     /// - [`u32`] - literal value
     LoadNat32,
+
+    /// Loads 16-bit natural literal into register.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - destination register
+    /// - [`u16`] - literal value
+    LoadNat16,
+
+    /// Loads 8-bit natural literal into register.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - destination register
+    /// - [`u8`] - literal value
+    LoadNat8,
+
+    /// Loads 64-bit integer literal into register.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - destination register
+    /// - [`i64`] - literal value
+    LoadInt64,
+
+    /// Loads 32-bit integer literal into register.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - destination register
+    /// - [`i32`] - literal value
+    LoadInt32,
+
+    /// Loads 16-bit integer literal into register.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - destination register
+    /// - [`i16`] - literal value
+    LoadInt16,
+
+    /// Loads 8-bit integer literal into register.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - destination register
+    /// - [`i8`] - literal value
+    LoadInt8,
+
+    /// Loads 64-bit floating point literal into register.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - destination register
+    /// - [`f64`] - literal value
+    LoadFloat64,
+
+    /// Loads 32-bit floating point literal into register.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - destination register
+    /// - [`f32`] - literal value
+    LoadFloat32,
+
+    // ! Arithmetic
+    /// Adds two 32-bit natural values, wrapping around on overflow.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - destination register
+    /// - [`RegisterId`] - left-hand side register
+    /// - [`RegisterId`] - right-hand side register
+    AddNat32,
+
+    /// Subtracts one 32-bit natural value from another, wrapping around on overflow.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - destination register
+    /// - [`RegisterId`] - left-hand side register
+    /// - [`RegisterId`] - right-hand side register
+    SubNat32,
+
+    // ! Control Flow
+    /// Calls a function.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - register receiving the returned value
+    /// - [`FunctionId`] - called function
+    /// - [`u8`] - number of arguments
+    /// - [`RegisterId`]... - registers passed as arguments
+    Call,
+
+    /// Returns from the current function.
+    ///
+    /// # Layout
+    ///
+    /// - [`RegisterId`] - register holding the returned value
+    Return,
 }
 
 #[derive(Debug, PartialEq)]
 pub enum IrInstruction<'container> {
     // ! Local Memory
+    AllocRegisterNat64(RegisterId, RegisterChill),
     AllocRegisterNat32(RegisterId, RegisterChill),
+    AllocRegisterNat16(RegisterId, RegisterChill),
+    AllocRegisterNat8(RegisterId, RegisterChill),
+    AllocRegisterInt64(RegisterId, RegisterChill),
+    AllocRegisterInt32(RegisterId, RegisterChill),
+    AllocRegisterInt16(RegisterId, RegisterChill),
+    AllocRegisterInt8(RegisterId, RegisterChill),
+    AllocRegisterFloat64(RegisterId, RegisterChill),
+    AllocRegisterFloat32(RegisterId, RegisterChill),
     DropRegister(RegisterId),
+    LoadNat64(RegisterId, u64),
     LoadNat32(RegisterId, u32),
+    LoadNat16(RegisterId, u16),
+    LoadNat8(RegisterId, u8),
+    LoadInt64(RegisterId, i64),
+    LoadInt32(RegisterId, i32),
+    LoadInt16(RegisterId, i16),
+    LoadInt8(RegisterId, i8),
+    LoadFloat64(RegisterId, f64),
+    LoadFloat32(RegisterId, f32),
+    // ! Arithmetic
+    AddNat32(RegisterId, RegisterId, RegisterId),
+    SubNat32(RegisterId, RegisterId, RegisterId),
     // ! Control Flow
-    Call(&'container [u8]),
+    Call(RegisterId, FunctionId, RegisterIds<'container>),
+    Return(RegisterId),
+}
+
+/// [`RegisterId`]s stored in an IR container, in their encoded form.
+#[derive(Clone, Copy, PartialEq)]
+pub struct RegisterIds<'container> {
+    bytes: &'container [u8],
+}
+
+impl<'container> RegisterIds<'container> {
+    /// # Panics
+    /// If the length of `bytes` is not a multiple of the size of [`RegisterId`].
+    pub(crate) fn from_bytes(bytes: &'container [u8]) -> Self {
+        assert_eq!(bytes.len() % mem::size_of::<RegisterId>(), 0);
+        Self { bytes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len() / mem::size_of::<RegisterId>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RegisterId> + 'container {
+        self.bytes
+            .chunks_exact(mem::size_of::<RegisterId>())
+            .map(|bytes| RegisterId(u16::from_le_bytes([bytes[0], bytes[1]])))
+    }
+}
+
+impl fmt::Debug for RegisterIds<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
 }
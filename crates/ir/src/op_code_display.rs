@@ -0,0 +1,202 @@
+use std::io;
+
+use rokugo_backend_common::FunctionId;
+use rokugo_common::color::{ColorSpec, ColoredDisplay};
+use termcolor::{Color, WriteColor};
+
+use crate::{
+    instruction_read_error::IrInstructionReadError,
+    op_code::IrInstruction,
+    register::{chill::RegisterChill, RegisterId},
+};
+
+const COLOR_LOCAL_MEMORY: ColorSpec = ColorSpec {
+    fg: Some(Color::Blue),
+    intense: true,
+};
+const COLOR_ARITHMETIC: ColorSpec = COLOR_LOCAL_MEMORY;
+const COLOR_CONTROL_FLOW: ColorSpec = COLOR_LOCAL_MEMORY;
+const COLOR_META: ColorSpec = ColorSpec {
+    fg: Some(Color::Black),
+    ..ColorSpec::default()
+};
+const COLOR_ERROR: ColorSpec = ColorSpec {
+    fg: Some(Color::Red),
+    intense: true,
+};
+
+const COLOR_REGISTER_ID: ColorSpec = ColorSpec {
+    fg: Some(Color::Yellow),
+    ..ColorSpec::default()
+};
+const COLOR_FUNCTION_ID: ColorSpec = ColorSpec {
+    fg: Some(Color::Green),
+    ..ColorSpec::default()
+};
+
+fn write_register_id(f: &mut dyn WriteColor, register_id: &RegisterId) -> io::Result<()> {
+    f.set_color(&COLOR_REGISTER_ID.into())?;
+    write!(f, "{}", register_id)
+}
+
+fn write_function_id(f: &mut dyn WriteColor, function_id: &FunctionId) -> io::Result<()> {
+    f.set_color(&COLOR_FUNCTION_ID.into())?;
+    write!(f, "{}", function_id)
+}
+
+fn write_alloc_register(
+    f: &mut dyn WriteColor,
+    name: &str,
+    register_id: &RegisterId,
+    chill: &RegisterChill,
+) -> io::Result<()> {
+    f.set_color(&COLOR_LOCAL_MEMORY.into())?;
+    write!(f, "{name} ")?;
+    write_register_id(f, register_id)?;
+    f.set_color(&COLOR_META.into())?;
+    write!(f, ", chill={}", chill)
+}
+
+fn write_binary(
+    f: &mut dyn WriteColor,
+    name: &str,
+    result: &RegisterId,
+    lhs: &RegisterId,
+    rhs: &RegisterId,
+) -> io::Result<()> {
+    f.set_color(&COLOR_ARITHMETIC.into())?;
+    write!(f, "{name} ")?;
+    write_register_id(f, result)?;
+    f.reset()?;
+    write!(f, ", ")?;
+    write_register_id(f, lhs)?;
+    f.reset()?;
+    write!(f, ", ")?;
+    write_register_id(f, rhs)
+}
+
+fn write_load(
+    f: &mut dyn WriteColor,
+    name: &str,
+    register_id: &RegisterId,
+    value: &dyn std::fmt::Display,
+) -> io::Result<()> {
+    f.set_color(&COLOR_LOCAL_MEMORY.into())?;
+    write!(f, "{name} ")?;
+    write_register_id(f, register_id)?;
+    f.reset()?;
+    write!(f, ", {}", value)
+}
+
+impl ColoredDisplay for IrInstruction<'_> {
+    fn fmt_with_color(&self, f: &mut dyn WriteColor) -> io::Result<()> {
+        match self {
+            // ! Local Memory
+            IrInstruction::AllocRegisterNat64(register_id, chill) => {
+                write_alloc_register(f, "AllocRegisterNat64", register_id, chill)?
+            }
+            IrInstruction::AllocRegisterNat32(register_id, chill) => {
+                write_alloc_register(f, "AllocRegisterNat32", register_id, chill)?
+            }
+            IrInstruction::AllocRegisterNat16(register_id, chill) => {
+                write_alloc_register(f, "AllocRegisterNat16", register_id, chill)?
+            }
+            IrInstruction::AllocRegisterNat8(register_id, chill) => {
+                write_alloc_register(f, "AllocRegisterNat8", register_id, chill)?
+            }
+            IrInstruction::AllocRegisterInt64(register_id, chill) => {
+                write_alloc_register(f, "AllocRegisterInt64", register_id, chill)?
+            }
+            IrInstruction::AllocRegisterInt32(register_id, chill) => {
+                write_alloc_register(f, "AllocRegisterInt32", register_id, chill)?
+            }
+            IrInstruction::AllocRegisterInt16(register_id, chill) => {
+                write_alloc_register(f, "AllocRegisterInt16", register_id, chill)?
+            }
+            IrInstruction::AllocRegisterInt8(register_id, chill) => {
+                write_alloc_register(f, "AllocRegisterInt8", register_id, chill)?
+            }
+            IrInstruction::AllocRegisterFloat64(register_id, chill) => {
+                write_alloc_register(f, "AllocRegisterFloat64", register_id, chill)?
+            }
+            IrInstruction::AllocRegisterFloat32(register_id, chill) => {
+                write_alloc_register(f, "AllocRegisterFloat32", register_id, chill)?
+            }
+            IrInstruction::DropRegister(register_id) => {
+                f.set_color(&COLOR_LOCAL_MEMORY.into())?;
+                write!(f, "DropRegister ")?;
+                write_register_id(f, register_id)?;
+            }
+            IrInstruction::LoadNat64(register_id, value) => {
+                write_load(f, "LoadNat64", register_id, value)?
+            }
+            IrInstruction::LoadNat32(register_id, value) => {
+                write_load(f, "LoadNat32", register_id, value)?
+            }
+            IrInstruction::LoadNat16(register_id, value) => {
+                write_load(f, "LoadNat16", register_id, value)?
+            }
+            IrInstruction::LoadNat8(register_id, value) => {
+                write_load(f, "LoadNat8", register_id, value)?
+            }
+            IrInstruction::LoadInt64(register_id, value) => {
+                write_load(f, "LoadInt64", register_id, value)?
+            }
+            IrInstruction::LoadInt32(register_id, value) => {
+                write_load(f, "LoadInt32", register_id, value)?
+            }
+            IrInstruction::LoadInt16(register_id, value) => {
+                write_load(f, "LoadInt16", register_id, value)?
+            }
+            IrInstruction::LoadInt8(register_id, value) => {
+                write_load(f, "LoadInt8", register_id, value)?
+            }
+            IrInstruction::LoadFloat64(register_id, value) => {
+                write_load(f, "LoadFloat64", register_id, value)?
+            }
+            IrInstruction::LoadFloat32(register_id, value) => {
+                write_load(f, "LoadFloat32", register_id, value)?
+            }
+            // ! Arithmetic
+            IrInstruction::AddNat32(result, lhs, rhs) => {
+                write_binary(f, "AddNat32", result, lhs, rhs)?
+            }
+            IrInstruction::SubNat32(result, lhs, rhs) => {
+                write_binary(f, "SubNat32", result, lhs, rhs)?
+            }
+            // ! Control Flow
+            IrInstruction::Call(result, function_id, arguments) => {
+                f.set_color(&COLOR_CONTROL_FLOW.into())?;
+                write!(f, "Call ")?;
+                write_register_id(f, result)?;
+                f.reset()?;
+                write!(f, ", ")?;
+                write_function_id(f, function_id)?;
+                for argument in arguments.iter() {
+                    f.reset()?;
+                    write!(f, ", ")?;
+                    write_register_id(f, &argument)?;
+                }
+            }
+            IrInstruction::Return(register_id) => {
+                f.set_color(&COLOR_CONTROL_FLOW.into())?;
+                write!(f, "Return ")?;
+                write_register_id(f, register_id)?;
+            }
+        }
+
+        f.reset()?;
+        writeln!(f)?;
+        Ok(())
+    }
+}
+
+impl ColoredDisplay for IrInstructionReadError {
+    fn fmt_with_color(&self, f: &mut dyn WriteColor) -> io::Result<()> {
+        f.set_color(&COLOR_ERROR.into())?;
+        write!(f, "error: {}", self)?;
+        f.reset()?;
+        writeln!(f)?;
+        Ok(())
+    }
+}
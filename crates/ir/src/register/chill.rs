@@ -1,5 +1,7 @@
 #![doc = include_str!("../../../../docs/ir/register/chilling.md")]
 
+use std::fmt;
+
 /// Container which holds the registers which are most optimal to chill in the current context.
 ///
 /// # Remarks
@@ -20,3 +22,10 @@ impl RegisterChill {
         []
     }
 }
+
+impl fmt::Display for RegisterChill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Registers to chill are not tracked yet, so the list is always empty.
+        write!(f, "[]")
+    }
+}
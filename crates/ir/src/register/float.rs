@@ -0,0 +1,58 @@
+use super::{Register, RegisterId};
+
+/// Flag for any size floating point register.
+pub trait RegisterFloat: Register {}
+
+/// 64-bit floating point register.
+pub struct RegisterFloat64(RegisterId);
+
+impl RegisterFloat64 {
+    /// # Safety
+    /// This function is unsafe because it can cause a compiler or runtime panic if the `id` is not properly.
+    pub unsafe fn new_unchecked(id: RegisterId) -> Self {
+        Self(id)
+    }
+}
+
+impl RegisterFloat for RegisterFloat64 {}
+impl Register for RegisterFloat64 {
+    fn id(&self) -> RegisterId {
+        self.0
+    }
+}
+
+/// 32-bit floating point register.
+pub struct RegisterFloat32(RegisterId);
+
+impl RegisterFloat32 {
+    /// # Safety
+    /// This function is unsafe because it can cause a compiler or runtime panic if the `id` is not properly.
+    pub unsafe fn new_unchecked(id: RegisterId) -> Self {
+        Self(id)
+    }
+}
+
+impl RegisterFloat for RegisterFloat32 {}
+impl Register for RegisterFloat32 {
+    fn id(&self) -> RegisterId {
+        self.0
+    }
+}
+
+/// 16-bit floating point register.
+pub struct RegisterFloat16(RegisterId);
+
+impl RegisterFloat16 {
+    /// # Safety
+    /// This function is unsafe because it can cause a compiler or runtime panic if the `id` is not properly.
+    pub unsafe fn new_unchecked(id: RegisterId) -> Self {
+        Self(id)
+    }
+}
+
+impl RegisterFloat for RegisterFloat16 {}
+impl Register for RegisterFloat16 {
+    fn id(&self) -> RegisterId {
+        self.0
+    }
+}
@@ -0,0 +1,76 @@
+use super::{Register, RegisterId};
+
+/// Flag for any size integer register.
+pub trait RegisterInt: Register {}
+
+/// 64-bit integer register.
+pub struct RegisterInt64(RegisterId);
+
+impl RegisterInt64 {
+    /// # Safety
+    /// This function is unsafe because it can cause a compiler or runtime panic if the `id` is not properly.
+    pub unsafe fn new_unchecked(id: RegisterId) -> Self {
+        Self(id)
+    }
+}
+
+impl RegisterInt for RegisterInt64 {}
+impl Register for RegisterInt64 {
+    fn id(&self) -> RegisterId {
+        self.0
+    }
+}
+
+/// 32-bit integer register.
+pub struct RegisterInt32(RegisterId);
+
+impl RegisterInt32 {
+    /// # Safety
+    /// This function is unsafe because it can cause a compiler or runtime panic if the `id` is not properly.
+    pub unsafe fn new_unchecked(id: RegisterId) -> Self {
+        Self(id)
+    }
+}
+
+impl RegisterInt for RegisterInt32 {}
+impl Register for RegisterInt32 {
+    fn id(&self) -> RegisterId {
+        self.0
+    }
+}
+
+/// 16-bit integer register.
+pub struct RegisterInt16(RegisterId);
+
+impl RegisterInt16 {
+    /// # Safety
+    /// This function is unsafe because it can cause a compiler or runtime panic if the `id` is not properly.
+    pub unsafe fn new_unchecked(id: RegisterId) -> Self {
+        Self(id)
+    }
+}
+
+impl RegisterInt for RegisterInt16 {}
+impl Register for RegisterInt16 {
+    fn id(&self) -> RegisterId {
+        self.0
+    }
+}
+
+/// 8-bit integer register.
+pub struct RegisterInt8(RegisterId);
+
+impl RegisterInt8 {
+    /// # Safety
+    /// This function is unsafe because it can cause a compiler or runtime panic if the `id` is not properly.
+    pub unsafe fn new_unchecked(id: RegisterId) -> Self {
+        Self(id)
+    }
+}
+
+impl RegisterInt for RegisterInt8 {}
+impl Register for RegisterInt8 {
+    fn id(&self) -> RegisterId {
+        self.0
+    }
+}
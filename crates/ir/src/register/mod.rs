@@ -1,6 +1,8 @@
-use std::ops::Range;
+use std::{fmt, ops::Range};
 
 pub mod chill;
+pub mod float;
+pub mod integer;
 pub mod natural;
 pub mod special;
 
@@ -31,14 +33,91 @@ pub const SPECIAL_ID_RANGE: Range<u16> = id_range(11);
 pub const PTR_ID_RANGE: Range<u16> = id_range(12);
 pub const BIT_ID_RANGE: Range<u16> = id_range(13);
 
+/// Class of a register, determining what type of values it stores.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegisterClass {
+    // Natural
+    Nat64,
+    Nat32,
+    Nat16,
+    Nat8,
+
+    // Integer
+    Int64,
+    Int32,
+    Int16,
+    Int8,
+
+    // Float
+    Float64,
+    Float32,
+    Float16,
+
+    // Misc
+    Special,
+    Ptr,
+    Bit,
+}
+
+impl RegisterClass {
+    /// All register classes, in the order of their ID ranges.
+    pub(crate) const ALL: [RegisterClass; 14] = [
+        Self::Nat64,
+        Self::Nat32,
+        Self::Nat16,
+        Self::Nat8,
+        Self::Int64,
+        Self::Int32,
+        Self::Int16,
+        Self::Int8,
+        Self::Float64,
+        Self::Float32,
+        Self::Float16,
+        Self::Special,
+        Self::Ptr,
+        Self::Bit,
+    ];
+
+    /// Range of [`RegisterId`]s belonging to this class.
+    pub const fn range(self) -> Range<u16> {
+        match self {
+            Self::Nat64 => NAT64_ID_RANGE,
+            Self::Nat32 => NAT32_ID_RANGE,
+            Self::Nat16 => NAT16_ID_RANGE,
+            Self::Nat8 => NAT8_ID_RANGE,
+            Self::Int64 => INT64_ID_RANGE,
+            Self::Int32 => INT32_ID_RANGE,
+            Self::Int16 => INT16_ID_RANGE,
+            Self::Int8 => INT8_ID_RANGE,
+            Self::Float64 => FLOAT64_ID_RANGE,
+            Self::Float32 => FLOAT32_ID_RANGE,
+            Self::Float16 => FLOAT16_ID_RANGE,
+            Self::Special => SPECIAL_ID_RANGE,
+            Self::Ptr => PTR_ID_RANGE,
+            Self::Bit => BIT_ID_RANGE,
+        }
+    }
+}
+
 pub trait Register {
     fn id(&self) -> RegisterId;
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub struct RegisterId(u16);
+pub struct RegisterId(pub(crate) u16);
 
 impl RegisterId {
+    /// Returns the class of register this id belongs to.
+    ///
+    /// # Panics
+    /// Panics if the id is outside of every class' range.
+    pub fn class(self) -> RegisterClass {
+        match RegisterClass::ALL.get(usize::from(self.0 / RANGE)) {
+            Some(&class) => class,
+            None => panic!("register id {} does not belong to any class", self.0),
+        }
+    }
+
     /// Converts the register id to little-endian bytes.
     pub fn to_le_bytes(self) -> [u8; 2] {
         self.0.to_le_bytes()
@@ -49,3 +128,9 @@ impl RegisterId {
         self.0
     }
 }
+
+impl fmt::Display for RegisterId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "%r{}", self.0)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    container::IrContainer, instruction_read_error::IrInstructionReadError, op_code::IrOpCode,
+};
+
+#[test]
+fn truncated_call() {
+    let mut data = vec![];
+    data.extend_from_slice(&(IrOpCode::Call as u16).to_le_bytes());
+    data.extend_from_slice(&1024u16.to_le_bytes());
+    data.extend_from_slice(&3u64.to_le_bytes());
+    data.push(2);
+    data.extend_from_slice(&1024u16.to_le_bytes());
+
+    // SAFETY: The call is truncated, which must be reported rather than read out of bounds.
+    let ir = unsafe { IrContainer::from_vec(data) };
+    let mut iter = ir.iter();
+    assert!(matches!(
+        iter.next(),
+        Some(Err(IrInstructionReadError::UnexpectedEof { at: 13 }))
+    ));
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn truncated_load() {
+    let mut data = vec![];
+    data.extend_from_slice(&(IrOpCode::LoadNat32 as u16).to_le_bytes());
+    data.extend_from_slice(&1024u16.to_le_bytes());
+    data.push(42);
+
+    // SAFETY: The load is truncated, which must be reported rather than read out of bounds.
+    let ir = unsafe { IrContainer::from_vec(data) };
+    let mut iter = ir.iter();
+    assert!(matches!(
+        iter.next(),
+        Some(Err(IrInstructionReadError::UnexpectedEof { at: 4 }))
+    ));
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn truncated_op_code() {
+    // SAFETY: The op code is truncated, which must be reported rather than read out of bounds.
+    let ir = unsafe { IrContainer::from_vec(vec![0]) };
+    assert!(matches!(
+        ir.iter().next(),
+        Some(Err(IrInstructionReadError::UnexpectedEof { at: 0 }))
+    ));
+}
@@ -0,0 +1,3 @@
+mod container;
+mod op_code_display;
+mod register;
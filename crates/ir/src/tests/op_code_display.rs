@@ -0,0 +1,23 @@
+use rokugo_common::color::ColoredDisplay;
+use termcolor::NoColor;
+
+use crate::{container::IrContainer, op_code::IrOpCode};
+
+#[test]
+fn display_invalid_op_code() {
+    let mut data = vec![];
+    data.extend_from_slice(&(IrOpCode::DropRegister as u16).to_le_bytes());
+    data.extend_from_slice(&3072u16.to_le_bytes());
+    data.extend_from_slice(&u16::MAX.to_le_bytes());
+    data.extend_from_slice(&(IrOpCode::DropRegister as u16).to_le_bytes());
+
+    // SAFETY: Decoding stops at the invalid op code, before reading anything after it.
+    let ir = unsafe { IrContainer::from_vec(data) };
+    let mut output = NoColor::new(vec![]);
+    ir.fmt_with_color(&mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output.into_inner()).unwrap(),
+        "DropRegister %r3072\n\
+         error: Invalid op code `65535` at index: 4\n"
+    );
+}
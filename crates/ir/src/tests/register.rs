@@ -0,0 +1,28 @@
+use crate::register::{RegisterClass, RegisterId};
+
+#[test]
+fn class() {
+    for class in RegisterClass::ALL {
+        let range = class.range();
+        assert_eq!(RegisterId(range.start).class(), class);
+        assert_eq!(RegisterId(range.start + 1).class(), class);
+        assert_eq!(RegisterId(range.end - 1).class(), class);
+    }
+}
+
+#[test]
+fn class_boundaries() {
+    assert_eq!(RegisterId(0).class(), RegisterClass::Nat64);
+    assert_eq!(RegisterId(1023).class(), RegisterClass::Nat64);
+    assert_eq!(RegisterId(1024).class(), RegisterClass::Nat32);
+    assert_eq!(
+        RegisterId(RegisterClass::Bit.range().end - 1).class(),
+        RegisterClass::Bit
+    );
+}
+
+#[test]
+#[should_panic(expected = "does not belong to any class")]
+fn class_out_of_range() {
+    RegisterId(RegisterClass::Bit.range().end).class();
+}
@@ -0,0 +1,72 @@
+//! Interning of identifier-like token text.
+
+use std::{ops::Range, sync::Arc};
+
+use rustc_hash::FxHashMap;
+
+/// Interned piece of source text, obtained from an [`Interner`].
+///
+/// Two symbols from the same interner are equal if and only if the text they were interned from
+/// is equal, so comparing symbols can replace comparing slices of source code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Symbol(u32);
+
+/// Spans of interned tokens, paired with their symbols.
+pub type SymbolSpans = Vec<(Range<usize>, Symbol)>;
+
+/// Maps strings to [`Symbol`]s and back.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    /// Each string is allocated once, and shared between the two directions of the mapping.
+    symbols: FxHashMap<Arc<str>, Symbol>,
+    strings: Vec<Arc<str>>,
+}
+
+impl Interner {
+    /// Construct an empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the symbol for the given text, allocating a new one if the text was not interned
+    /// before.
+    ///
+    /// # Panics
+    ///
+    /// If more than [`u32::MAX`] distinct strings are interned.
+    pub fn intern(&mut self, text: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(text) {
+            return symbol;
+        }
+        let symbol = Symbol(u32::try_from(self.strings.len()).expect("too many interned strings"));
+        let text: Arc<str> = text.into();
+        self.strings.push(Arc::clone(&text));
+        self.symbols.insert(text, symbol);
+        symbol
+    }
+
+    /// Returns the symbol for the given text, if it was interned before.
+    pub fn get(&self, text: &str) -> Option<Symbol> {
+        self.symbols.get(text).copied()
+    }
+
+    /// Returns the text a symbol was interned from.
+    ///
+    /// # Panics
+    ///
+    /// If the symbol does not come from this interner.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.0 as usize]
+    }
+
+    /// Returns the number of distinct strings interned so far.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns whether no strings were interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
@@ -1,24 +1,45 @@
 use std::ops::Range;
 
-use rokugo_diagnostic::{note, Diagnostic, Importance, NoteKind, Severity};
-use rokugo_source_code::{FileId, SourceSpan};
+use rokugo_diagnostic::{
+    note, Applicability, Diagnostic, Importance, NoteKind, Severity, Suggestion,
+};
+use rokugo_source_code::{FileId, SourceSpan, Sources};
 
 use crate::token::{Token, TokenKind};
 
 /// Lexer state.
+///
+/// The lexer is an [`Iterator`] producing tokens lazily, together with any diagnostics emitted
+/// while lexing them. Diagnostics are yielded right after the token they were emitted for, so the
+/// stream is in source order.
 pub struct Lexer<'a> {
-    pub file_id: FileId,
-    pub input: &'a str,
-    pub position: usize,
-    pub tokens: Vec<Token>,
-    pub diagnostics: Vec<Diagnostic>,
+    pub(crate) file_id: FileId,
+    pub(crate) input: &'a str,
+    pub(crate) position: usize,
+    pub(crate) tokens: Vec<Token>,
+    pub(crate) diagnostics: Vec<Diagnostic>,
 }
 
 impl<'a> Lexer<'a> {
+    /// Creates a lexer for a file from the given source set.
+    pub fn new(sources: &'a Sources, file_id: FileId) -> Self {
+        Self {
+            file_id,
+            input: &sources.get(file_id).source,
+            position: 0,
+            tokens: vec![],
+            diagnostics: vec![],
+        }
+    }
+
     fn current(&self) -> Option<char> {
         self.input[self.position..].chars().next()
     }
 
+    fn next_char(&self) -> Option<char> {
+        self.input[self.position..].chars().nth(1)
+    }
+
     fn advance(&mut self) {
         self.position += self.current().map(|c| c.len_utf8()).unwrap_or(0);
     }
@@ -53,7 +74,13 @@ impl<'a> Lexer<'a> {
             self.advance();
         }
         let end = self.position;
-        self.tokens.push(TokenKind::Comment.at(start..end));
+        // A `#!` line at the very start of the file lets the file be run as a script.
+        let kind = if start == 0 && self.input.starts_with("#!") {
+            TokenKind::Shebang
+        } else {
+            TokenKind::Comment
+        };
+        self.tokens.push(kind.at(start..end));
     }
 
     fn decimal_number_literal(&mut self) {
@@ -63,19 +90,17 @@ impl<'a> Lexer<'a> {
             self.advance();
         }
         let decimal_point_start = self.position;
-        if let Some('.') = self.current() {
+        // `..` after an integer is a range operator rather than a decimal point.
+        if self.current() == Some('.') && self.next_char() != Some('.') {
             kind = TokenKind::Decimal;
             self.advance();
             let decimal_point_end = self.position;
             if !matches!(self.current(), Some('0'..='9')) {
-                self.diagnostics.push(
-                    Severity::Error
-                        .diagnostic("decimal point `.` must be followed by at least one digit")
-                        .with_label(
-                            Importance::Primary
-                                .label(self.span(decimal_point_start..decimal_point_end), ""),
-                        ),
-                );
+                self.diagnostics.push(Diagnostic::error_at(
+                    self.span(decimal_point_start..decimal_point_end),
+                    "decimal point `.` must be followed by at least one digit",
+                    "",
+                ));
             }
             while let Some('0'..='9') = self.current() {
                 self.advance();
@@ -91,6 +116,9 @@ impl<'a> Lexer<'a> {
                 let escaped_start = self.position;
                 let escaped = self.current();
                 let escaped_end = self.position;
+                if escaped == Some('\n') {
+                    return;
+                }
                 self.advance();
                 if escaped == Some('u') {
                     if self.current() == Some('{') {
@@ -132,69 +160,105 @@ impl<'a> Lexer<'a> {
     fn character_literal(&mut self) {
         let start = self.position;
         self.advance();
-        self.character_or_escape();
-        if self.current() != Some('\'') {
-            self.diagnostics.push(
-                Severity::Error
-                    .diagnostic("missing `'` after character literal")
-                    .with_label(
-                        Importance::Primary
-                            .label(self.span(start..self.position), "missing `'` after this"),
-                    ),
-            );
-            self.tokens.push(TokenKind::Error.at(start..self.position));
-        } else {
+        // A character literal never spans lines, so a line break right after the opening quote
+        // is not consumed as the character.
+        if !matches!(self.current(), Some('\n') | None) {
+            self.character_or_escape();
+        }
+        if self.current() == Some('\'') {
             self.advance();
             self.tokens
                 .push(TokenKind::Character.at(start..self.position));
+            return;
+        }
+
+        // Look for the closing quote, but only until the end of the line. If it's there, the
+        // literal has more than one character in it, and the whole literal becomes the error token.
+        // Otherwise the quote is missing, and the rest of the line is lexed as usual.
+        let end = self.position;
+        let closing_quote = self.input[end..]
+            .find(['\'', '\n'])
+            .map(|offset| end + offset)
+            .filter(|&position| self.input[position..].starts_with('\''));
+        if let Some(closing_quote) = closing_quote {
+            self.position = closing_quote + 1;
+            self.diagnostics.push(Diagnostic::error_at(
+                self.span(start..self.position),
+                "character literal may only contain one character",
+                "",
+            ));
+        } else {
+            self.diagnostics.push(Diagnostic::error_at(
+                self.span(start..end),
+                "missing `'` after character literal",
+                "missing `'` after this",
+            ));
         }
+        self.tokens.push(TokenKind::Error.at(start..self.position));
     }
 
     fn string_literal(&mut self) {
         let mut is_multiline = false;
+        let mut lone_carriage_returns = vec![];
 
         let start = self.position;
         self.advance();
         let after_quote = self.position;
         while self.current() != Some('"') {
-            self.advance();
-            if self.current() == Some('\n') {
-                is_multiline = true;
+            match self.current() {
+                Some('\n') => is_multiline = true,
+                // `\r\n` is a line break, and is reported as such.
+                Some('\r') if self.next_char() != Some('\n') => {
+                    lone_carriage_returns.push(self.position)
+                }
+                _ => (),
             }
+            self.advance();
             if self.current().is_none() {
-                self.diagnostics.push(
-                    Severity::Error
-                        .diagnostic("missing `\"` to close string literal")
-                        .with_label(Importance::Primary.label(
-                            self.span(start..after_quote),
-                            "missing `\"` to close this literal",
-                        )),
-                );
+                self.diagnostics.push(Diagnostic::error_at(
+                    self.span(start..after_quote),
+                    "missing `\"` to close string literal",
+                    "missing `\"` to close this literal",
+                ));
                 break;
             }
         }
         self.advance(); // skip "
 
         if is_multiline {
+            self.diagnostics.push(Diagnostic::error_at(
+                self.span(start..self.position),
+                "string literals may not span multiple lines",
+                "this literal spans multiple lines",
+            ));
+        }
+        for position in lone_carriage_returns {
+            let span = self.span(position..position + 1);
             self.diagnostics.push(
-                Severity::Error
-                    .diagnostic("string literals may not span multiple lines")
-                    .with_label(Importance::Primary.label(
-                        self.span(start..self.position),
-                        "this literal spans multiple lines",
-                    )),
+                Diagnostic::error_at(
+                    span.clone(),
+                    "string literals may not contain bare carriage returns",
+                    "use the `\\r` escape sequence instead",
+                )
+                .with_suggestion(Suggestion {
+                    source_span: span,
+                    replacement: "\\r".into(),
+                    applicability: Applicability::MachineApplicable,
+                }),
             );
         }
 
         self.tokens.push(TokenKind::String.at(start..self.position));
     }
 
+    /// Identifiers follow Unicode's `XID_Start` and `XID_Continue` rules, with the addition of `_`
+    /// being allowed at the start.
     fn is_identifier_start_char(c: Option<char>) -> bool {
-        matches!(c, Some('a'..='z' | 'A'..='Z' | '_'))
+        matches!(c, Some(c) if c == '_' || unicode_ident::is_xid_start(c))
     }
 
     fn is_identifier_char(c: Option<char>) -> bool {
-        Self::is_identifier_start_char(c) || matches!(c, Some('0'..='9'))
+        matches!(c, Some(c) if unicode_ident::is_xid_continue(c))
     }
 
     fn identifier(&mut self) {
@@ -271,6 +335,7 @@ impl<'a> Lexer<'a> {
         let operator = &self.input[start..end];
         let kind = match operator {
             "." => TokenKind::Dot,
+            ".." => TokenKind::DotDot,
             ":" => TokenKind::Colon,
             "=" => TokenKind::Equals,
             "|" => TokenKind::Pipe,
@@ -287,9 +352,7 @@ impl<'a> Lexer<'a> {
                         .with_note(note(NoteKind::Note, "tag names must be valid identifiers.\nidentifiers cannot start with digits because they could be confused with numbers")),
                 )
             }
-            while Self::is_identifier_char(self.current()) {
-                self.advance();
-            }
+            self.tag_name();
             let end = self.position;
             self.tokens.push(TokenKind::Tag.at(start..end));
         } else {
@@ -297,53 +360,102 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    /// Lexis loop.
-    ///
-    /// This lexer pushes tokens out to a [`Vec<Token>`], which can later be read via
-    /// [`Lexer::tokens`]. It may also emit diagnostics while lexing, and these will be visible in
-    /// [`Lexer::diagnostics`].
-    pub fn lex(&mut self) {
+    /// Tag names are identifiers, whose words may be separated by single hyphens, as in `:utf-8`.
+    fn tag_name(&mut self) {
         loop {
-            self.skip_whitespace();
-
-            if let Some(c) = self.current() {
-                match c {
-                    '\n' => self.single_char_token(TokenKind::Newline),
-                    '#' => self.comment(),
-
-                    '(' => self.single_char_token(TokenKind::LParen),
-                    ')' => self.single_char_token(TokenKind::RParen),
-                    '[' => self.single_char_token(TokenKind::LBracket),
-                    ']' => self.single_char_token(TokenKind::RBracket),
-                    '{' => self.single_char_token(TokenKind::LBrace),
-                    '}' => self.single_char_token(TokenKind::RBrace),
-                    ',' => self.single_char_token(TokenKind::Comma),
-                    ';' => self.single_char_token(TokenKind::Semicolon),
-
-                    '0'..='9' => self.decimal_number_literal(),
-                    '\'' => self.character_literal(),
-                    '"' => self.string_literal(),
-                    _ if Self::is_identifier_start_char(Some(c)) => self.identifier(),
-                    _ if Self::is_operator_char(Some(c)) => self.operator(),
-
-                    _ => {
-                        let start = self.position;
-                        self.advance();
-                        let span = start..self.position;
-                        self.diagnostics.push(
-                            Severity::Error
-                                .diagnostic(format!("unexpected `{}`", c))
-                                .with_label(Importance::Primary.label(
-                                    self.span(span.clone()),
-                                    "this character is not valid in Rokugo source code",
-                                )),
-                        );
-                        self.tokens.push(self.token(start, TokenKind::Error));
-                    }
-                }
+            while Self::is_identifier_char(self.current()) {
+                self.advance();
+            }
+            if self.current() != Some('-') {
+                break;
+            }
+            let next = self.next_char();
+            if Self::is_identifier_char(next) {
+                self.advance();
+            } else if Self::is_operator_char(next) {
+                // Part of an operator such as `->`, which is not part of the tag.
+                break;
             } else {
+                let hyphen = self.position;
+                self.advance();
+                self.diagnostics.push(
+                    Diagnostic::error_at(
+                        self.span(hyphen..self.position),
+                        "tag name cannot end with `-`",
+                        "",
+                    )
+                    .with_note(note(
+                        NoteKind::Note,
+                        "hyphens in tag names must be followed by another word, as in `:utf-8`",
+                    )),
+                );
                 break;
             }
         }
     }
+
+    /// Lexes a single token starting at the current position, skipping any whitespace before it.
+    /// The token is pushed to [`Lexer::tokens`], and any diagnostics to [`Lexer::diagnostics`].
+    ///
+    /// Returns `false` once the end of input is reached and no token could be lexed.
+    pub(crate) fn lex_token(&mut self) -> bool {
+        self.skip_whitespace();
+
+        let Some(c) = self.current() else {
+            return false;
+        };
+        match c {
+            '\n' => self.single_char_token(TokenKind::Newline),
+            '#' => self.comment(),
+
+            '(' => self.single_char_token(TokenKind::LParen),
+            ')' => self.single_char_token(TokenKind::RParen),
+            '[' => self.single_char_token(TokenKind::LBracket),
+            ']' => self.single_char_token(TokenKind::RBracket),
+            '{' => self.single_char_token(TokenKind::LBrace),
+            '}' => self.single_char_token(TokenKind::RBrace),
+            ',' => self.single_char_token(TokenKind::Comma),
+            ';' => self.single_char_token(TokenKind::Semicolon),
+
+            '0'..='9' => self.decimal_number_literal(),
+            '\'' => self.character_literal(),
+            '"' => self.string_literal(),
+            _ if Self::is_identifier_start_char(Some(c)) => self.identifier(),
+            _ if Self::is_operator_char(Some(c)) => self.operator(),
+
+            _ => {
+                let start = self.position;
+                self.advance();
+                let span = start..self.position;
+                self.diagnostics.push(Diagnostic::error_at(
+                    self.span(span.clone()),
+                    format!("unexpected `{}`", c),
+                    "this character is not valid in Rokugo source code",
+                ));
+                self.tokens.push(self.token(start, TokenKind::Error));
+            }
+        }
+        true
+    }
+
+    /// Skips whitespace and returns the position at which the next token will start.
+    pub(crate) fn next_token_start(&mut self) -> usize {
+        self.skip_whitespace();
+        self.position
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Result<Token, Diagnostic>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.tokens.is_empty() && self.diagnostics.is_empty() && !self.lex_token() {
+            return None;
+        }
+        if !self.tokens.is_empty() {
+            Some(Ok(self.tokens.remove(0)))
+        } else {
+            Some(Err(self.diagnostics.remove(0)))
+        }
+    }
 }
@@ -1,20 +1,122 @@
-use lexer::Lexer;
+use std::ops::Range;
+
+use intern::{Interner, SymbolSpans};
 use rokugo_diagnostic::Diagnostic;
 use rokugo_source_code::{FileId, Sources};
-use token::Token;
+use token::{Token, TokenKind};
 
+pub mod intern;
 mod lexer;
 pub mod token;
 
+pub use lexer::Lexer;
+
 /// Lex a file from the given source set into a set of tokens and diagnostics.
+///
+/// See [`Lexer`] for lexing a file lazily.
 pub fn lex(sources: &Sources, file_id: FileId) -> (Vec<Token>, Vec<Diagnostic>) {
-    let mut lexer = Lexer {
-        file_id,
-        input: &sources.get(file_id).source,
-        position: 0,
-        tokens: vec![],
-        diagnostics: vec![],
-    };
-    lexer.lex();
-    (lexer.tokens, lexer.diagnostics)
+    let mut tokens = vec![];
+    let mut diagnostics = vec![];
+    for result in Lexer::new(sources, file_id) {
+        match result {
+            Ok(token) => tokens.push(token),
+            Err(diagnostic) => diagnostics.push(diagnostic),
+        }
+    }
+    (tokens, diagnostics)
+}
+
+/// Same as [`lex`], but additionally interns the text of every [`Identifier`][TokenKind::Identifier],
+/// [`Operator`][TokenKind::Operator], and [`Tag`][TokenKind::Tag] token.
+///
+/// The tokens themselves are the same as returned by [`lex`]. The third element of the returned
+/// tuple pairs the span of each interned token with its [`Symbol`][intern::Symbol], in source order. Tags are
+/// interned together with their leading `:`, so a tag never shares a symbol with an identifier.
+pub fn lex_interned(
+    sources: &Sources,
+    file_id: FileId,
+    interner: &mut Interner,
+) -> (Vec<Token>, Vec<Diagnostic>, SymbolSpans) {
+    let source = &sources.get(file_id).source;
+    let (tokens, diagnostics) = lex(sources, file_id);
+    let symbols = tokens
+        .iter()
+        .filter(|token| {
+            matches!(
+                token.kind,
+                TokenKind::Identifier | TokenKind::Operator | TokenKind::Tag
+            )
+        })
+        .map(|token| {
+            let symbol = interner.intern(&source[token.span.clone()]);
+            (token.span.clone(), symbol)
+        })
+        .collect();
+    (tokens, diagnostics, symbols)
+}
+
+/// Re-lex a file after an edit, reusing tokens from a previous [`lex`] of the file wherever
+/// possible.
+///
+/// `old_tokens` are the tokens of the file before the edit. `edit` is the range of bytes in the
+/// old source that got replaced, and `new_len` is the length of the text that replaced it. The
+/// file inside `sources` must already contain the edited source code.
+///
+/// Lexing restarts at the beginning of the line the edit starts in, and stops as soon as it
+/// reaches a token that also started a token in the old source. Tokens after that point are
+/// reused, with their spans shifted by the difference in length. If no newline precedes the edit,
+/// the file is re-lexed from the start.
+///
+/// Note that only diagnostics from the re-lexed region are returned. Diagnostics emitted for the
+/// remaining tokens during the previous lex are still valid, but their spans must be shifted the
+/// same way as the tokens that come after the edit.
+pub fn relex(
+    sources: &Sources,
+    file_id: FileId,
+    old_tokens: &[Token],
+    edit: Range<usize>,
+    new_len: usize,
+) -> (Vec<Token>, Vec<Diagnostic>) {
+    // Newline tokens are never part of another token, and the lexer does not carry any state
+    // between tokens, so it's always safe to restart right after one.
+    let reused_prefix = old_tokens
+        .iter()
+        .rposition(|token| token.kind == TokenKind::Newline && token.span.end <= edit.start)
+        .map(|index| index + 1)
+        .unwrap_or(0);
+    let restart = reused_prefix
+        .checked_sub(1)
+        .map(|index| old_tokens[index].span.end)
+        .unwrap_or(0);
+
+    let mut lexer = Lexer::new(sources, file_id);
+    lexer.position = restart;
+    let mut tokens = old_tokens[..reused_prefix].to_vec();
+    let mut diagnostics = vec![];
+
+    let shift = |position: usize| position - edit.len() + new_len;
+    let mut trailing = old_tokens.partition_point(|token| token.span.start < edit.end);
+    loop {
+        let start = lexer.next_token_start();
+        while trailing < old_tokens.len() && shift(old_tokens[trailing].span.start) < start {
+            trailing += 1;
+        }
+        if trailing < old_tokens.len() && shift(old_tokens[trailing].span.start) == start {
+            // The source from here on is identical to the source before the edit, so lexing it
+            // would yield the same tokens.
+            tokens.extend(old_tokens[trailing..].iter().map(|token| {
+                token
+                    .kind
+                    .at(shift(token.span.start)..shift(token.span.end))
+            }));
+            break;
+        }
+        if !lexer.lex_token() {
+            break;
+        }
+        tokens.append(&mut lexer.tokens);
+        diagnostics.append(&mut lexer.diagnostics);
+    }
+
+    (tokens, diagnostics)
 }
@@ -1,68 +1,112 @@
-use std::ops::Range;
+use std::{error::Error, fmt, ops::Range};
 
-/// Kind of a token.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum TokenKind {
-    Error,
+use rokugo_diagnostic::Diagnostic;
+use rokugo_source_code::SourceSpan;
 
-    // Metadata
-    Comment,
-    Newline,
+/// Declares [`TokenKind`] along with [`TokenKind::ALL`], so that the list of all kinds can never
+/// get out of sync with the enum.
+macro_rules! token_kinds {
+    (
+        $(#[$meta:meta])*
+        pub enum TokenKind {
+            $($variant:ident,)*
+        }
+    ) => {
+        $(#[$meta])*
+        pub enum TokenKind {
+            $($variant,)*
+        }
+
+        impl TokenKind {
+            /// All token kinds, in the order of their declaration.
+            pub const ALL: [TokenKind; [$(stringify!($variant)),*].len()] = [$(Self::$variant),*];
+        }
+    };
+}
+
+token_kinds! {
+    /// Kind of a token.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum TokenKind {
+        Error,
+
+        // Metadata
+        Comment,
+        Newline,
+        Shebang,
+
+        // Literals
+        Integer,
+        Decimal,
+        Character,
+        String,
+        Tag,
+
+        // Names
+        Identifier,
+        Operator,
 
-    // Literals
-    Integer,
-    Decimal,
-    Character,
+        // Punctuators
+        LParen,    // (
+        RParen,    // )
+        LBracket,  // [
+        RBracket,  // ]
+        LBrace,    // {
+        RBrace,    // }
+        Comma,     // ,
+        Semicolon, // ;
+
+        // Keywords
+        And,
+        Break,
+        Default,
+        Do,
+        Effect,
+        Else,
+        Fun,
+        Handle,
+        If,
+        Interface,
+        Internal,
+        Is,
+        Let,
+        Match,
+        Module,
+        Mut,
+        Or,
+        Set,
+        Then,
+        Underscore, // _
+        Use,
+        Var,
+        While,
+        With,
+
+        // Reserved operators
+        Dot,       // .
+        DotDot,    // ..
+        Colon,     // :
+        Equals,    // =
+        Pipe,      // |
+        Ampersand, // &
+        At,        // @
+    }
+}
+
+/// Category of a token for the purposes of syntax highlighting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HighlightKind {
+    Keyword,
+    Operator,
     String,
+    Number,
     Tag,
-
-    // Names
+    Comment,
     Identifier,
-    Operator,
-
-    // Punctuators
-    LParen,    // (
-    RParen,    // )
-    LBracket,  // [
-    RBracket,  // ]
-    LBrace,    // {
-    RBrace,    // }
-    Comma,     // ,
-    Semicolon, // ;
-
-    // Keywords
-    And,
-    Break,
-    Default,
-    Do,
-    Effect,
-    Else,
-    Fun,
-    Handle,
-    If,
-    Interface,
-    Internal,
-    Is,
-    Let,
-    Match,
-    Module,
-    Mut,
-    Or,
-    Set,
-    Then,
-    Underscore, // _
-    Use,
-    Var,
-    While,
-    With,
-
-    // Reserved operators
-    Dot,       // .
-    Colon,     // :
-    Equals,    // =
-    Pipe,      // |
-    Ampersand, // &
-    At,        // @
+    Punctuation,
+    /// Not highlighted in any special way. Newlines and invalid characters fall into this category.
+    Plain,
 }
 
 /// A single token, representing a specific lexical construct at a given source span.
@@ -71,6 +115,7 @@ pub enum TokenKind {
 /// only read characters and categorise them into tokens without trying to parse them into usable
 /// data.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token {
     pub kind: TokenKind,
     pub span: Range<usize>,
@@ -81,4 +126,235 @@ impl TokenKind {
     pub fn at(self, span: Range<usize>) -> Token {
         Token { kind: self, span }
     }
+
+    /// Returns the category this kind of token should be highlighted as in editors.
+    ///
+    /// Since operators are identifiers in Rokugo, this only tells operators and identifiers apart
+    /// by how they're spelled, not by how they're used.
+    pub fn highlight(self) -> HighlightKind {
+        if self.is_keyword() {
+            return HighlightKind::Keyword;
+        }
+        match self {
+            Self::Error | Self::Newline => HighlightKind::Plain,
+            Self::Comment | Self::Shebang => HighlightKind::Comment,
+            Self::Integer | Self::Decimal => HighlightKind::Number,
+            Self::Character | Self::String => HighlightKind::String,
+            Self::Tag => HighlightKind::Tag,
+            Self::Identifier => HighlightKind::Identifier,
+            Self::Operator
+            | Self::Dot
+            | Self::DotDot
+            | Self::Colon
+            | Self::Equals
+            | Self::Pipe
+            | Self::Ampersand
+            | Self::At => HighlightKind::Operator,
+            // Brackets, commas, and semicolons.
+            _ => HighlightKind::Punctuation,
+        }
+    }
+
+    /// Whether this kind of token is insignificant to the syntax of the program: comments,
+    /// newlines, and shebangs.
+    ///
+    /// Note that while newlines do not form any syntax on their own, they can still be used to
+    /// tell where a construct ends.
+    pub fn is_trivia(self) -> bool {
+        matches!(self, Self::Comment | Self::Newline | Self::Shebang)
+    }
+
+    /// Whether this kind of token is a literal value.
+    pub fn is_literal(self) -> bool {
+        matches!(
+            self,
+            Self::Integer | Self::Decimal | Self::Character | Self::String | Self::Tag
+        )
+    }
+
+    /// Whether this kind of token is a keyword, including `_`.
+    pub fn is_keyword(self) -> bool {
+        matches!(
+            self,
+            Self::And
+                | Self::Break
+                | Self::Default
+                | Self::Do
+                | Self::Effect
+                | Self::Else
+                | Self::Fun
+                | Self::Handle
+                | Self::If
+                | Self::Interface
+                | Self::Internal
+                | Self::Is
+                | Self::Let
+                | Self::Match
+                | Self::Module
+                | Self::Mut
+                | Self::Or
+                | Self::Set
+                | Self::Then
+                | Self::Underscore
+                | Self::Use
+                | Self::Var
+                | Self::While
+                | Self::With
+        )
+    }
+
+    /// Whether this kind of token is an operator with special meaning, which cannot be redefined.
+    pub fn is_reserved_operator(self) -> bool {
+        matches!(
+            self,
+            Self::Dot
+                | Self::DotDot
+                | Self::Colon
+                | Self::Equals
+                | Self::Pipe
+                | Self::Ampersand
+                | Self::At
+        )
+    }
+}
+
+/// Category of an [`Operator`][TokenKind::Operator] token, determined by its text.
+///
+/// Operators are user-defined functions, so this only says what an operator is commonly used
+/// for, not what it does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatorKind {
+    /// `+` and `-`.
+    Additive,
+    /// `*`, `/`, and `%`.
+    Multiplicative,
+    /// `==`, `!=`, `<`, `<=`, `>`, and `>=`.
+    Comparison,
+    /// `|>` and `<|`, which apply a function to a value.
+    Pipe,
+    /// `->`, the function type operator.
+    Arrow,
+    /// `=>`, which separates a pattern from its expression in a `match` arm.
+    ///
+    /// Like `=` and the other magic operators, it has a special meaning to the compiler, so it
+    /// cannot be defined by users. Unlike them, it's lexed as a plain
+    /// [`Operator`][TokenKind::Operator] rather than a token kind of its own, because it's only
+    /// meaningful inside `match`, which is up to the parser to recognize.
+    Reserved,
+    /// Any other operator.
+    Custom,
+}
+
+/// Classify the text of an [`Operator`][TokenKind::Operator] token.
+pub fn classify_operator(text: &str) -> OperatorKind {
+    match text {
+        "+" | "-" => OperatorKind::Additive,
+        "*" | "/" | "%" => OperatorKind::Multiplicative,
+        "==" | "!=" | "<" | "<=" | ">" | ">=" => OperatorKind::Comparison,
+        "|>" | "<|" => OperatorKind::Pipe,
+        "->" => OperatorKind::Arrow,
+        // Must not be classified as a custom operator, as it cannot be defined by users.
+        "=>" => OperatorKind::Reserved,
+        _ => OperatorKind::Custom,
+    }
+}
+
+/// Problem found while interpreting the text of a numeric literal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LiteralError {
+    /// The literal does not contain any digits, such as `0x` or `_`.
+    NoDigits,
+    /// The literal contains a character which is not a digit in its base. `position` is the byte
+    /// offset of the character in the literal's text.
+    InvalidDigit { position: usize, digit: char },
+    /// The literal's value is too large to be represented.
+    Overflow,
+}
+
+impl LiteralError {
+    /// Convert the error to a diagnostic pointing at the literal, which is located at `source_span`.
+    pub fn to_diagnostic(&self, source_span: SourceSpan) -> Diagnostic {
+        let label = match self {
+            LiteralError::NoDigits => "at least one digit expected",
+            LiteralError::InvalidDigit { .. } => "this literal is malformed",
+            LiteralError::Overflow => "this literal is too large",
+        };
+        Diagnostic::error_at(source_span, self.to_string(), label)
+    }
+}
+
+impl fmt::Display for LiteralError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiteralError::NoDigits => write!(f, "numeric literal has no digits"),
+            LiteralError::InvalidDigit { digit, .. } => {
+                write!(f, "invalid digit `{digit}` in numeric literal")
+            }
+            LiteralError::Overflow => write!(f, "numeric literal is too large"),
+        }
+    }
+}
+
+impl Error for LiteralError {}
+
+/// Interpret the text of an [`Integer`][TokenKind::Integer] literal.
+///
+/// Literals are decimal by default, hexadecimal if prefixed with `0x`, and binary if prefixed with
+/// `0b`. Digits may be separated with underscores, which are ignored.
+pub fn parse_integer(text: &str) -> Result<i128, LiteralError> {
+    let (radix, digits_start) = match text.get(..2) {
+        Some("0x" | "0X") => (16, 2),
+        Some("0b" | "0B") => (2, 2),
+        _ => (10, 0),
+    };
+
+    let mut value: Option<i128> = None;
+    for (position, c) in text.char_indices().skip(digits_start) {
+        if c == '_' {
+            continue;
+        }
+        let digit = c
+            .to_digit(radix)
+            .ok_or(LiteralError::InvalidDigit { position, digit: c })?;
+        value = Some(
+            value
+                .unwrap_or(0)
+                .checked_mul(i128::from(radix))
+                .and_then(|value| value.checked_add(i128::from(digit)))
+                .ok_or(LiteralError::Overflow)?,
+        );
+    }
+    value.ok_or(LiteralError::NoDigits)
+}
+
+/// Interpret the text of a [`Decimal`][TokenKind::Decimal] literal.
+///
+/// Decimal literals consist of decimal digits, with an optional decimal point. Digits may be
+/// separated with underscores, which are ignored.
+pub fn parse_decimal(text: &str) -> Result<f64, LiteralError> {
+    let mut digits = String::with_capacity(text.len());
+    let mut seen_point = false;
+    for (position, c) in text.char_indices() {
+        match c {
+            '0'..='9' => digits.push(c),
+            '.' if !seen_point => {
+                seen_point = true;
+                digits.push(c);
+            }
+            '_' => (),
+            _ => return Err(LiteralError::InvalidDigit { position, digit: c }),
+        }
+    }
+    if !digits.bytes().any(|b| b.is_ascii_digit()) {
+        return Err(LiteralError::NoDigits);
+    }
+
+    // The digits were validated above, so they're always a valid float.
+    let value: f64 = digits
+        .parse()
+        .expect("decimal digits should parse as a float");
+    if value.is_infinite() {
+        return Err(LiteralError::Overflow);
+    }
+    Ok(value)
 }
@@ -0,0 +1,50 @@
+use rokugo_lexis::{intern::Interner, lex, lex_interned};
+use rokugo_source_code::{File, Sources};
+
+#[test]
+fn same_text_same_symbol() {
+    let (sources, file_id) = Sources::with_one("intern.rk", "let x = y + x\nlet y = :x + x");
+    let mut interner = Interner::new();
+    let (tokens, diagnostics, symbols) = lex_interned(&sources, file_id, &mut interner);
+    assert!(diagnostics.is_empty());
+    assert_eq!((tokens, diagnostics), lex(&sources, file_id));
+
+    let spans: Vec<_> = symbols.iter().map(|(span, _)| span.clone()).collect();
+    assert_eq!(
+        spans,
+        &[4..5, 8..9, 10..11, 12..13, 18..19, 22..24, 25..26, 27..28]
+    );
+    let symbols: Vec<_> = symbols.into_iter().map(|(_, symbol)| symbol).collect();
+    let [x1, y1, plus1, x2, y2, tag_x, plus2, x3] = symbols[..] else {
+        unreachable!()
+    };
+    assert_eq!(x1, x2);
+    assert_eq!(x1, x3);
+    assert_eq!(y1, y2);
+    assert_eq!(plus1, plus2);
+    assert_ne!(x1, y1);
+    assert_ne!(x1, tag_x);
+
+    assert_eq!(interner.resolve(x1), "x");
+    assert_eq!(interner.resolve(tag_x), ":x");
+    assert_eq!(interner.get("y"), Some(y1));
+    assert_eq!(interner.get("z"), None);
+}
+
+#[test]
+fn shared_between_files() {
+    let mut sources = Sources::default();
+    let a = sources.add(File {
+        filename: "a.rk".into(),
+        source: "print hello".into(),
+    });
+    let b = sources.add(File {
+        filename: "b.rk".into(),
+        source: "hello world".into(),
+    });
+    let mut interner = Interner::new();
+    let (_, _, a_symbols) = lex_interned(&sources, a, &mut interner);
+    let (_, _, b_symbols) = lex_interned(&sources, b, &mut interner);
+    assert_eq!(a_symbols[1].1, b_symbols[0].1);
+    assert_eq!(interner.len(), 3);
+}
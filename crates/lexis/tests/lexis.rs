@@ -1,14 +1,17 @@
-use rokugo_diagnostic::{Diagnostic, Importance, Output, Severity};
-use rokugo_lexis::token::{Token, TokenKind};
-use rokugo_source_code::{File, FileId, Sources};
+use std::ops::Range;
+
+use rokugo_diagnostic::{
+    note, Applicability, Diagnostic, Importance, NoteKind, Output, Severity, Suggestion,
+};
+use rokugo_lexis::{
+    token::{Token, TokenKind},
+    Lexer,
+};
+use rokugo_source_code::{FileId, Sources};
 
 #[track_caller]
 fn lex(filename: &str, source: &str) -> (Sources, FileId, Vec<Token>, Vec<Diagnostic>) {
-    let mut sources = Sources::default();
-    let file_id = sources.add(File {
-        filename: filename.into(),
-        source: source.into(),
-    });
+    let (sources, file_id) = Sources::with_one(filename, source);
     let (tokens, diagnostics) = rokugo_lexis::lex(&sources, file_id);
     (sources, file_id, tokens, diagnostics)
 }
@@ -90,6 +93,30 @@ fn comment() {
     );
 }
 
+#[test]
+fn shebang() {
+    assert_eq!(
+        nice("shebang", "#!/usr/bin/env rokugo\nx"),
+        &[
+            TokenKind::Shebang.at(0..21),
+            TokenKind::Newline.at(21..22),
+            TokenKind::Identifier.at(22..23),
+        ]
+    );
+    assert_eq!(
+        nice("shebang not at start", "x\n#!/usr/bin/env rokugo"),
+        &[
+            TokenKind::Identifier.at(0..1),
+            TokenKind::Newline.at(1..2),
+            TokenKind::Comment.at(2..23),
+        ]
+    );
+    assert_eq!(
+        nice("shebang after whitespace", " #!/usr/bin/env rokugo"),
+        &[TokenKind::Comment.at(1..22)]
+    );
+}
+
 #[test]
 fn integer() {
     assert_eq!(nice("integer 1", "1"), &[TokenKind::Integer.at(0..1)]);
@@ -131,6 +158,58 @@ fn escapes() {
     );
 }
 
+#[test]
+fn character_unterminated() {
+    naughty(
+        "character unterminated",
+        "'ab",
+        &[TokenKind::Error.at(0..2), TokenKind::Identifier.at(2..3)],
+        |file_id| {
+            vec![Diagnostic::error_at(
+                file_id.span(0..2),
+                "missing `'` after character literal",
+                "missing `'` after this",
+            )]
+        },
+    );
+    naughty(
+        "character unterminated before line break",
+        "'a\nb'",
+        &[
+            TokenKind::Error.at(0..2),
+            TokenKind::Newline.at(2..3),
+            TokenKind::Identifier.at(3..4),
+            TokenKind::Error.at(4..5),
+        ],
+        |file_id| {
+            vec![
+                Diagnostic::error_at(
+                    file_id.span(0..2),
+                    "missing `'` after character literal",
+                    "missing `'` after this",
+                ),
+                Diagnostic::error_at(
+                    file_id.span(4..5),
+                    "missing `'` after character literal",
+                    "missing `'` after this",
+                ),
+            ]
+        },
+    );
+    naughty(
+        "character too long",
+        "'abc' x",
+        &[TokenKind::Error.at(0..5), TokenKind::Identifier.at(6..7)],
+        |file_id| {
+            vec![Diagnostic::error_at(
+                file_id.span(0..5),
+                "character literal may only contain one character",
+                "",
+            )]
+        },
+    );
+}
+
 #[test]
 fn string() {
     assert_eq!(
@@ -143,6 +222,45 @@ fn string() {
     );
 }
 
+#[test]
+fn string_carriage_return() {
+    // `\r\n` is a line break, which is reported as such.
+    naughty(
+        "string crlf",
+        "\"a\r\nb\"",
+        &[TokenKind::String.at(0..6)],
+        |file_id| {
+            vec![Diagnostic::error_at(
+                file_id.span(0..6),
+                "string literals may not span multiple lines",
+                "this literal spans multiple lines",
+            )]
+        },
+    );
+    naughty(
+        "string lone carriage return",
+        "\"\ra\rb\"",
+        &[TokenKind::String.at(0..6)],
+        |file_id| {
+            [1, 3]
+                .into_iter()
+                .map(|position| {
+                    Diagnostic::error_at(
+                        file_id.span(position..position + 1),
+                        "string literals may not contain bare carriage returns",
+                        "use the `\\r` escape sequence instead",
+                    )
+                    .with_suggestion(Suggestion {
+                        source_span: file_id.span(position..position + 1),
+                        replacement: "\\r".into(),
+                        applicability: Applicability::MachineApplicable,
+                    })
+                })
+                .collect()
+        },
+    );
+}
+
 #[test]
 fn identifier() {
     assert_eq!(
@@ -165,6 +283,47 @@ fn identifier() {
         nice("identifier digits", "a1234"),
         &[TokenKind::Identifier.at(0..5)]
     );
+    assert_eq!(
+        nice("identifier digit start", "1abc"),
+        &[TokenKind::Integer.at(0..1), TokenKind::Identifier.at(1..4)]
+    );
+}
+
+#[test]
+fn unicode_identifier() {
+    assert_eq!(
+        nice("identifier Polish", "pełnia"),
+        &[TokenKind::Identifier.at(0..7)]
+    );
+    assert_eq!(
+        nice("identifier Greek", "λ"),
+        &[TokenKind::Identifier.at(0..2)]
+    );
+    assert_eq!(
+        nice("identifier underscore", "_λ"),
+        &[TokenKind::Identifier.at(0..3)]
+    );
+    assert_eq!(
+        nice("identifier combining mark", "e\u{301}te\u{301}"),
+        &[TokenKind::Identifier.at(0..7)]
+    );
+    naughty(
+        "identifier starting with combining mark",
+        "\u{301}e",
+        &[TokenKind::Error.at(0..2), TokenKind::Identifier.at(2..3)],
+        |file_id| {
+            vec![Severity::Error
+                .diagnostic("unexpected `\u{301}`")
+                .with_label(Importance::Primary.label(
+                    file_id.span(0..2),
+                    "this character is not valid in Rokugo source code",
+                ))]
+        },
+    );
+    assert_eq!(
+        nice("keyword next to Unicode", "let łódź"),
+        &[TokenKind::Let.at(0..3), TokenKind::Identifier.at(4..11)]
+    );
 }
 
 #[test]
@@ -194,7 +353,7 @@ fn operator() {
 #[test]
 fn reserved_operators() {
     assert_eq!(
-        nice("reserved operators", ". : = | & @"),
+        nice("reserved operators", ". : = | & @ .."),
         &[
             TokenKind::Dot.at(0..1),
             TokenKind::Colon.at(2..3),
@@ -202,6 +361,43 @@ fn reserved_operators() {
             TokenKind::Pipe.at(6..7),
             TokenKind::Ampersand.at(8..9),
             TokenKind::At.at(10..11),
+            TokenKind::DotDot.at(12..14),
+        ]
+    );
+}
+
+#[test]
+fn ranges() {
+    assert_eq!(
+        nice("integer range", "1..10"),
+        &[
+            TokenKind::Integer.at(0..1),
+            TokenKind::DotDot.at(1..3),
+            TokenKind::Integer.at(3..5),
+        ]
+    );
+    assert_eq!(
+        nice("character range", "'a'..'z'"),
+        &[
+            TokenKind::Character.at(0..3),
+            TokenKind::DotDot.at(3..5),
+            TokenKind::Character.at(5..8),
+        ]
+    );
+    assert_eq!(
+        nice("open start range", "..5"),
+        &[TokenKind::DotDot.at(0..2), TokenKind::Integer.at(2..3)]
+    );
+    assert_eq!(
+        nice("open end range", "x.."),
+        &[TokenKind::Identifier.at(0..1), TokenKind::DotDot.at(1..3)]
+    );
+    assert_eq!(
+        nice("decimal range", "1.5..2.5"),
+        &[
+            TokenKind::Decimal.at(0..3),
+            TokenKind::DotDot.at(3..5),
+            TokenKind::Decimal.at(5..8),
         ]
     );
 }
@@ -209,6 +405,44 @@ fn reserved_operators() {
 #[test]
 fn tags() {
     assert_eq!(nice("tags", ":hug"), &[TokenKind::Tag.at(0..4)]);
+    assert_eq!(nice("tags hyphen", ":my-tag"), &[TokenKind::Tag.at(0..7)]);
+    assert_eq!(
+        nice("tags hyphens", ":utf-8 :a-b-c"),
+        &[TokenKind::Tag.at(0..6), TokenKind::Tag.at(7..13)]
+    );
+    assert_eq!(
+        nice("tags arrow", ":ok->"),
+        &[TokenKind::Tag.at(0..3), TokenKind::Operator.at(3..5)]
+    );
+}
+
+#[test]
+fn tags_malformed() {
+    naughty(
+        "tags trailing hyphen",
+        ":my- x",
+        &[TokenKind::Tag.at(0..4), TokenKind::Identifier.at(5..6)],
+        |file_id| {
+            vec![
+                Diagnostic::error_at(file_id.span(3..4), "tag name cannot end with `-`", "")
+                    .with_note(note(
+                        NoteKind::Note,
+                        "hyphens in tag names must be followed by another word, as in `:utf-8`",
+                    )),
+            ]
+        },
+    );
+    naughty(
+        "tags digit start",
+        ":1st",
+        &[TokenKind::Tag.at(0..4)],
+        |file_id| {
+            vec![Severity::Error
+                .diagnostic("tag name cannot start with a number")
+                .with_label(Importance::Primary.label(file_id.span(1..2), ""))
+                .with_note(note(NoteKind::Note, "tag names must be valid identifiers.\nidentifiers cannot start with digits because they could be confused with numbers"))]
+        },
+    );
 }
 
 #[test]
@@ -244,3 +478,87 @@ fn keywords() {
         ]
     );
 }
+
+#[track_caller]
+fn relex(old_source: &str, edit: Range<usize>, replacement: &str) -> (Vec<Token>, Vec<Diagnostic>) {
+    let (_sources, _file_id, old_tokens, _diagnostics) = lex("relex old", old_source);
+
+    let mut new_source = old_source.to_owned();
+    new_source.replace_range(edit.clone(), replacement);
+    let (sources, file_id, new_tokens, _diagnostics) = lex("relex new", &new_source);
+
+    let (tokens, diagnostics) =
+        rokugo_lexis::relex(&sources, file_id, &old_tokens, edit, replacement.len());
+    assert_eq!(
+        tokens, new_tokens,
+        "re-lexing must match lexing from scratch"
+    );
+    (tokens, diagnostics)
+}
+
+#[test]
+fn relex_middle_of_file() {
+    let (tokens, _) = relex("a = 1\nb = 2\nc = 3\n", 10..11, "42");
+    assert_eq!(
+        tokens,
+        &[
+            TokenKind::Identifier.at(0..1),
+            TokenKind::Equals.at(2..3),
+            TokenKind::Integer.at(4..5),
+            TokenKind::Newline.at(5..6),
+            TokenKind::Identifier.at(6..7),
+            TokenKind::Equals.at(8..9),
+            TokenKind::Integer.at(10..12),
+            TokenKind::Newline.at(12..13),
+            TokenKind::Identifier.at(13..14),
+            TokenKind::Equals.at(15..16),
+            TokenKind::Integer.at(17..18),
+            TokenKind::Newline.at(18..19),
+        ]
+    );
+}
+
+#[test]
+fn relex_only_touches_edited_line() {
+    // The invalid characters on the first and last line are not lexed again, so their
+    // diagnostics are not reported a second time.
+    let (_, diagnostics) = relex("`\nb = 2\n`\n", 6..7, "3");
+    assert_eq!(diagnostics, &[]);
+
+    let (_, diagnostics) = relex("`\nb = 2\n`\n", 6..7, "`");
+    assert_eq!(diagnostics.len(), 1);
+}
+
+#[test]
+fn relex_edit_changes_following_lines() {
+    relex("a = 1\nb = 2\nc = \"3\"\n", 10..11, "\"2");
+    relex("a = \"1\nb\" = 2\n", 5..5, "\"");
+}
+
+#[test]
+fn relex_first_line() {
+    relex("abc = 1\n", 0..3, "a");
+    relex("abc = 1\n", 3..3, "def");
+    relex("", 0..0, "abc");
+}
+
+#[test]
+fn streaming() {
+    let (sources, file_id) = Sources::with_one("streaming", "a ` b\nc");
+    let mut lexer = Lexer::new(&sources, file_id);
+    assert_eq!(lexer.next(), Some(Ok(TokenKind::Identifier.at(0..1))));
+    assert_eq!(lexer.next(), Some(Ok(TokenKind::Error.at(2..3))));
+    assert_eq!(
+        lexer.next(),
+        Some(Err(Severity::Error
+            .diagnostic("unexpected ```")
+            .with_label(Importance::Primary.label(
+                file_id.span(2..3),
+                "this character is not valid in Rokugo source code",
+            ))))
+    );
+    assert_eq!(lexer.next(), Some(Ok(TokenKind::Identifier.at(4..5))));
+    assert_eq!(lexer.next(), Some(Ok(TokenKind::Newline.at(5..6))));
+    assert_eq!(lexer.next(), Some(Ok(TokenKind::Identifier.at(6..7))));
+    assert_eq!(lexer.next(), None);
+}
@@ -0,0 +1,98 @@
+use rokugo_diagnostic::Diagnostic;
+use rokugo_lexis::token::{parse_decimal, parse_integer, LiteralError};
+use rokugo_source_code::Sources;
+
+#[test]
+fn integer() {
+    assert_eq!(parse_integer("0"), Ok(0));
+    assert_eq!(parse_integer("123"), Ok(123));
+    assert_eq!(parse_integer("1_000_000"), Ok(1_000_000));
+    assert_eq!(parse_integer("0xff"), Ok(255));
+    assert_eq!(parse_integer("0XdEaD_bEeF"), Ok(0xdead_beef));
+    assert_eq!(parse_integer("0b1010"), Ok(10));
+    assert_eq!(parse_integer("007"), Ok(7));
+}
+
+#[test]
+fn integer_boundaries() {
+    assert_eq!(
+        parse_integer("170141183460469231731687303715884105727"),
+        Ok(i128::MAX)
+    );
+    assert_eq!(
+        parse_integer("170141183460469231731687303715884105728"),
+        Err(LiteralError::Overflow)
+    );
+    assert_eq!(
+        parse_integer("0x7fff_ffff_ffff_ffff_ffff_ffff_ffff_ffff"),
+        Ok(i128::MAX)
+    );
+    assert_eq!(
+        parse_integer("0x8000_0000_0000_0000_0000_0000_0000_0000"),
+        Err(LiteralError::Overflow)
+    );
+    assert_eq!(
+        parse_integer("99999999999999999999999999999999999999999999999999"),
+        Err(LiteralError::Overflow)
+    );
+}
+
+#[test]
+fn integer_malformed() {
+    assert_eq!(parse_integer(""), Err(LiteralError::NoDigits));
+    assert_eq!(parse_integer("0x"), Err(LiteralError::NoDigits));
+    assert_eq!(parse_integer("0b__"), Err(LiteralError::NoDigits));
+    assert_eq!(
+        parse_integer("0b102"),
+        Err(LiteralError::InvalidDigit {
+            position: 4,
+            digit: '2'
+        })
+    );
+    assert_eq!(
+        parse_integer("12a"),
+        Err(LiteralError::InvalidDigit {
+            position: 2,
+            digit: 'a'
+        })
+    );
+}
+
+#[test]
+fn decimal() {
+    assert_eq!(parse_decimal("1.5"), Ok(1.5));
+    assert_eq!(parse_decimal("1_000.000_1"), Ok(1000.0001));
+    assert_eq!(parse_decimal("42"), Ok(42.0));
+    assert_eq!(
+        parse_decimal("1".repeat(400).as_str()),
+        Err(LiteralError::Overflow)
+    );
+    assert_eq!(parse_decimal("_._"), Err(LiteralError::NoDigits));
+    assert_eq!(
+        parse_decimal("1.2.3"),
+        Err(LiteralError::InvalidDigit {
+            position: 3,
+            digit: '.'
+        })
+    );
+    assert_eq!(
+        parse_decimal("1e5"),
+        Err(LiteralError::InvalidDigit {
+            position: 1,
+            digit: 'e'
+        })
+    );
+}
+
+#[test]
+fn to_diagnostic() {
+    let (_, file_id) = Sources::with_one("test.rk", "let x = 0x\n");
+    assert_eq!(
+        LiteralError::NoDigits.to_diagnostic(file_id.span(8..10)),
+        Diagnostic::error_at(
+            file_id.span(8..10),
+            "numeric literal has no digits",
+            "at least one digit expected"
+        )
+    );
+}
@@ -0,0 +1,13 @@
+use rokugo_lexis::token::Token;
+use rokugo_source_code::Sources;
+
+#[test]
+fn round_trip() {
+    let (sources, file_id) = Sources::with_one("test.rk", "let x = 'a' + 1.5 # comment\n");
+    let (tokens, diagnostics) = rokugo_lexis::lex(&sources, file_id);
+    assert!(diagnostics.is_empty());
+
+    let json = serde_json::to_string(&tokens).unwrap();
+    let deserialized: Vec<Token> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, tokens);
+}
@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+
+use rokugo_lexis::{
+    lex,
+    token::{classify_operator, HighlightKind, OperatorKind, TokenKind},
+};
+use rokugo_source_code::Sources;
+
+#[test]
+fn all() {
+    let unique: HashSet<_> = TokenKind::ALL
+        .iter()
+        .map(|kind| format!("{kind:?}"))
+        .collect();
+    assert_eq!(unique.len(), TokenKind::ALL.len());
+    for (i, kind) in TokenKind::ALL.into_iter().enumerate() {
+        assert_eq!(kind as usize, i, "{kind:?} is out of declaration order");
+    }
+    assert_eq!(TokenKind::ALL.len(), TokenKind::At as usize + 1);
+}
+
+#[test]
+fn classification() {
+    let mut counts = [0; 4];
+    for kind in TokenKind::ALL {
+        let classes = [
+            kind.is_trivia(),
+            kind.is_literal(),
+            kind.is_keyword(),
+            kind.is_reserved_operator(),
+        ];
+        assert!(
+            classes.iter().filter(|&&class| class).count() <= 1,
+            "{kind:?} belongs to more than one class"
+        );
+        for (count, class) in counts.iter_mut().zip(classes) {
+            *count += usize::from(class);
+        }
+    }
+    assert_eq!(counts, [3, 5, 24, 7]);
+
+    assert!(TokenKind::Comment.is_trivia());
+    assert!(TokenKind::Tag.is_literal());
+    assert!(TokenKind::Underscore.is_keyword());
+    assert!(TokenKind::DotDot.is_reserved_operator());
+    for kind in [
+        TokenKind::Error,
+        TokenKind::Identifier,
+        TokenKind::Operator,
+        TokenKind::LParen,
+        TokenKind::Semicolon,
+    ] {
+        assert!(
+            !(kind.is_trivia()
+                || kind.is_literal()
+                || kind.is_keyword()
+                || kind.is_reserved_operator())
+        );
+    }
+}
+
+#[test]
+fn highlight() {
+    for kind in TokenKind::ALL {
+        let highlight = kind.highlight();
+        if kind.is_keyword() {
+            assert_eq!(highlight, HighlightKind::Keyword, "{kind:?}");
+        }
+        if kind.is_reserved_operator() {
+            assert_eq!(highlight, HighlightKind::Operator, "{kind:?}");
+        }
+        if kind.is_literal() {
+            assert!(
+                matches!(
+                    highlight,
+                    HighlightKind::Number | HighlightKind::String | HighlightKind::Tag
+                ),
+                "{kind:?}"
+            );
+        }
+    }
+    assert_eq!(TokenKind::Comment.highlight(), HighlightKind::Comment);
+    assert_eq!(TokenKind::Identifier.highlight(), HighlightKind::Identifier);
+    assert_eq!(TokenKind::Operator.highlight(), HighlightKind::Operator);
+    assert_eq!(TokenKind::Comma.highlight(), HighlightKind::Punctuation);
+    assert_eq!(TokenKind::Error.highlight(), HighlightKind::Plain);
+}
+
+#[test]
+fn operator_kinds() {
+    let cases = [
+        ("+", OperatorKind::Additive),
+        ("-", OperatorKind::Additive),
+        ("*", OperatorKind::Multiplicative),
+        ("/", OperatorKind::Multiplicative),
+        ("%", OperatorKind::Multiplicative),
+        ("==", OperatorKind::Comparison),
+        ("!=", OperatorKind::Comparison),
+        ("<", OperatorKind::Comparison),
+        ("<=", OperatorKind::Comparison),
+        (">", OperatorKind::Comparison),
+        (">=", OperatorKind::Comparison),
+        ("|>", OperatorKind::Pipe),
+        ("<|", OperatorKind::Pipe),
+        ("->", OperatorKind::Arrow),
+        ("=>", OperatorKind::Reserved),
+        ("++", OperatorKind::Custom),
+        ("<$>", OperatorKind::Custom),
+        ("~", OperatorKind::Custom),
+    ];
+    for (text, kind) in cases {
+        let (sources, file_id) = Sources::with_one("operator.rk", text);
+        let (tokens, _) = lex(&sources, file_id);
+        assert_eq!(tokens, [TokenKind::Operator.at(0..text.len())], "{text}");
+        assert_eq!(classify_operator(text), kind, "{text}");
+    }
+}
+
+#[test]
+fn match_arm_operator_is_reserved() {
+    let text = ":some x => x";
+    let (sources, file_id) = Sources::with_one("match.rk", text);
+    let (tokens, diagnostics) = lex(&sources, file_id);
+    assert!(diagnostics.is_empty());
+
+    let operators: Vec<_> = tokens
+        .iter()
+        .filter(|token| token.kind == TokenKind::Operator)
+        .map(|token| &text[token.span.clone()])
+        .collect();
+    assert_eq!(operators, ["=>"]);
+    assert_eq!(classify_operator("=>"), OperatorKind::Reserved);
+    // Only `=>` itself is reserved, not longer operators starting with it.
+    assert_eq!(classify_operator("=>>"), OperatorKind::Custom);
+}
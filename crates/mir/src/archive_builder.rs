@@ -37,7 +37,7 @@ impl ArchiveBuilderRef {
         signature_builder: FunctionSignatureBuilder<T>,
     ) -> FunctionBuilderRef<'_> {
         let function_id = unsafe {
-            std::mem::transmute(
+            std::mem::transmute::<u64, FunctionId>(
                 self.inner
                     .next_function_id
                     .fetch_add(1, std::sync::atomic::Ordering::Relaxed),
@@ -0,0 +1,70 @@
+use std::collections::{HashMap, HashSet};
+
+use rokugo_backend_common::ValueId;
+
+use crate::emit::{container::MirContainer, op_code::MirInstructionData};
+
+/// Removes instructions defining values that are never used, returning the optimized MIR.
+///
+/// A value is used if it is an operand of an instruction with side effects, or of an instruction
+/// defining another used value. Calls, returns, and jumps have side effects, and are therefore
+/// always kept, even if the value returned by a call is never used. So are divisions, since
+/// dividing by zero fails at runtime.
+pub fn eliminate_dead_values(container: &MirContainer) -> MirContainer {
+    let mut operands_of_pure: HashMap<ValueId, Vec<ValueId>> = HashMap::new();
+    let mut live = HashSet::new();
+    let mut worklist = vec![];
+
+    for instruction in container {
+        let data = &instruction.data;
+        match (data.result(), is_pure(data)) {
+            (Some(result), true) => {
+                operands_of_pure.insert(result, data.operands());
+            }
+            _ => worklist.extend(data.operands()),
+        }
+    }
+
+    while let Some(value_id) = worklist.pop() {
+        if live.insert(value_id) {
+            if let Some(operands) = operands_of_pure.get(&value_id) {
+                worklist.extend(operands.iter().copied());
+            }
+        }
+    }
+
+    let mut result = MirContainer { data: vec![] };
+    for instruction in container {
+        let is_dead = is_pure(&instruction.data)
+            && instruction
+                .data
+                .result()
+                .is_some_and(|value_id| !live.contains(&value_id));
+        if !is_dead {
+            result.emit_instruction(&instruction);
+        }
+    }
+    result
+}
+
+/// Whether an instruction can be removed if the value it defines is unused.
+fn is_pure(data: &MirInstructionData<'_>) -> bool {
+    match data {
+        // ! Memory
+        MirInstructionData::DefineNat32(_, _)
+        | MirInstructionData::DefineInt32(_, _)
+        | MirInstructionData::DefineFloat32(_, _)
+        | MirInstructionData::DefineFloat64(_, _) => true,
+        // ! Arithmetic
+        MirInstructionData::AddInt32(_, _, _)
+        | MirInstructionData::SubInt32(_, _, _)
+        | MirInstructionData::MulInt32(_, _, _) => true,
+        MirInstructionData::DivInt32(_, _, _) => false,
+        // ! Control flow
+        MirInstructionData::ReturnValue(_)
+        | MirInstructionData::Call(_, _, _)
+        | MirInstructionData::BindLabel(_)
+        | MirInstructionData::Jump(_)
+        | MirInstructionData::JumpIf(_, _) => false,
+    }
+}
@@ -1,27 +1,113 @@
 use std::{mem, ops::Range};
 
 use bytemuck::Pod;
+use rokugo_backend_common::ValueId;
 use rokugo_common::color::ColoredDisplay;
 
-use super::op_code::{MirInstruction, MirInstructionData, MirInstructionMeta, MirOpCode};
+use crate::verify::{MirError, MirErrorKind};
+
+use super::op_code::{MirInstruction, MirInstructionData, MirInstructionMeta, MirOpCode, ValueIds};
 
 #[derive(Debug)]
 pub struct MirContainer {
-    pub(super) data: Vec<u8>,
+    pub(crate) data: Vec<u8>,
 }
 
 impl MirContainer {
-    pub fn iter(&self) -> MirContainerIterator {
+    pub fn iter(&self) -> MirContainerIterator<'_> {
         MirContainerIterator {
             content: self,
             index: 0,
+            sticky_span: None,
         }
     }
 
-    pub(super) fn emit_native_bytes(&mut self, value: impl Pod) {
+    pub(crate) fn emit_native_bytes(&mut self, value: impl Pod) {
         let bytes = bytemuck::bytes_of(&value);
         self.data.extend_from_slice(bytes);
     }
+
+    /// Appends an instruction read from another container, preceded by a `MetaSpan` if it has a
+    /// span.
+    pub(crate) fn emit_instruction(&mut self, instruction: &MirInstruction<'_>) {
+        if let Some(span) = &instruction.meta.span {
+            self.emit_native_bytes(MirOpCode::MetaSpan as u8);
+            self.emit_native_bytes(span.start);
+            self.emit_native_bytes(span.end);
+        }
+
+        match instruction.data {
+            // ! Memory
+            MirInstructionData::DefineNat32(result, value) => {
+                self.emit_native_bytes(MirOpCode::DefineNat32 as u8);
+                self.emit_native_bytes(result);
+                self.emit_native_bytes(value);
+            }
+            MirInstructionData::DefineInt32(result, value) => {
+                self.emit_native_bytes(MirOpCode::DefineInt32 as u8);
+                self.emit_native_bytes(result);
+                self.emit_native_bytes(value);
+            }
+            MirInstructionData::DefineFloat32(result, value) => {
+                self.emit_native_bytes(MirOpCode::DefineFloat32 as u8);
+                self.emit_native_bytes(result);
+                self.emit_native_bytes(value);
+            }
+            MirInstructionData::DefineFloat64(result, value) => {
+                self.emit_native_bytes(MirOpCode::DefineFloat64 as u8);
+                self.emit_native_bytes(result);
+                self.emit_native_bytes(value);
+            }
+            // ! Arithmetic
+            MirInstructionData::AddInt32(result, lhs, rhs) => {
+                self.emit_binary(MirOpCode::AddInt32, result, lhs, rhs)
+            }
+            MirInstructionData::SubInt32(result, lhs, rhs) => {
+                self.emit_binary(MirOpCode::SubInt32, result, lhs, rhs)
+            }
+            MirInstructionData::MulInt32(result, lhs, rhs) => {
+                self.emit_binary(MirOpCode::MulInt32, result, lhs, rhs)
+            }
+            MirInstructionData::DivInt32(result, lhs, rhs) => {
+                self.emit_binary(MirOpCode::DivInt32, result, lhs, rhs)
+            }
+            // ! Control flow
+            MirInstructionData::ReturnValue(value_id) => {
+                self.emit_native_bytes(MirOpCode::ReturnValue as u8);
+                self.emit_native_bytes(value_id);
+            }
+            MirInstructionData::Call(result, function_id, arguments) => {
+                self.emit_native_bytes(MirOpCode::Call as u8);
+                self.emit_native_bytes(result);
+                self.emit_native_bytes(function_id);
+                // The arguments were read from a container, so their count already fits in a byte.
+                self.emit_native_bytes(arguments.len() as u8);
+                for argument in arguments.iter() {
+                    self.emit_native_bytes(argument);
+                }
+            }
+            MirInstructionData::BindLabel(label) => {
+                self.emit_native_bytes(MirOpCode::BindLabel as u8);
+                self.emit_native_bytes(label);
+            }
+            MirInstructionData::Jump(label) => {
+                self.emit_native_bytes(MirOpCode::Jump as u8);
+                self.emit_native_bytes(label);
+            }
+            MirInstructionData::JumpIf(condition, label) => {
+                self.emit_native_bytes(MirOpCode::JumpIf as u8);
+                self.emit_native_bytes(condition);
+                self.emit_native_bytes(label);
+            }
+        }
+    }
+
+    fn emit_binary(&mut self, op_code: MirOpCode, result: ValueId, lhs: ValueId, rhs: ValueId) {
+        self.emit_native_bytes(op_code as u8);
+        self.emit_native_bytes(result);
+        self.emit_native_bytes(lhs);
+        self.emit_native_bytes(rhs);
+    }
 }
 
 impl<'c> IntoIterator for &'c MirContainer {
@@ -36,81 +122,156 @@ impl<'c> IntoIterator for &'c MirContainer {
 pub struct MirContainerIterator<'c> {
     content: &'c MirContainer,
     index: usize,
+    /// Span set by the last `MetaSpanSticky`, attached to every instruction until the next span.
+    sticky_span: Option<Range<usize>>,
 }
 
 impl<'c> MirContainerIterator<'c> {
-    unsafe fn read_native<T>(&mut self) -> T {
-        let mut value = mem::MaybeUninit::<T>::uninit();
-        let ptr = value.as_mut_ptr() as *mut u8;
-        ptr.copy_from_nonoverlapping(
-            self.content.data.as_ptr().byte_add(self.index),
-            mem::size_of::<T>(),
-        );
-        self.index += mem::size_of::<T>();
-        value.assume_init()
+    fn read_native<T: Pod>(&mut self) -> Result<T, MirErrorKind> {
+        let end = self.index + mem::size_of::<T>();
+        let bytes = self
+            .content
+            .data
+            .get(self.index..end)
+            .ok_or(MirErrorKind::UnexpectedEnd)?;
+        self.index = end;
+        Ok(bytemuck::pod_read_unaligned(bytes))
     }
 
-    unsafe fn read_native_slice<T>(&mut self, count: usize) -> &'c [T] {
-        let ptr = self.content.data.as_ptr().byte_add(self.index) as *const T;
-        let slice = std::slice::from_raw_parts(ptr, count);
-        self.index += mem::size_of::<T>() * count;
-        slice
+    fn read_value_ids(&mut self, count: usize) -> Result<ValueIds<'c>, MirErrorKind> {
+        let end = self.index + mem::size_of::<ValueId>() * count;
+        let bytes = self
+            .content
+            .data
+            .get(self.index..end)
+            .ok_or(MirErrorKind::UnexpectedEnd)?;
+        self.index = end;
+        Ok(ValueIds::from_bytes(bytes))
     }
 
-    unsafe fn read_instruction(
+    fn read_op_code(&mut self) -> Result<MirOpCode, MirErrorKind> {
+        let op_code: u8 = self.read_native()?;
+        MirOpCode::from_u8(op_code).ok_or(MirErrorKind::InvalidOpCode(op_code))
+    }
+
+    fn read_instruction(
         &mut self,
         meta: &mut MirInstructionMeta,
-    ) -> Option<MirInstructionData<'c>> {
-        let op_code: MirOpCode = self.read_native();
-        match op_code {
+    ) -> Result<Option<MirInstructionData<'c>>, MirErrorKind> {
+        let op_code = self.read_op_code()?;
+        Ok(match op_code {
             // ! Memory
             MirOpCode::DefineNat32 => Some(MirInstructionData::DefineNat32(
-                self.read_native(),
-                self.read_native(),
+                self.read_native()?,
+                self.read_native()?,
             )),
             MirOpCode::DefineInt32 => Some(MirInstructionData::DefineInt32(
-                self.read_native(),
-                self.read_native(),
+                self.read_native()?,
+                self.read_native()?,
+            )),
+            MirOpCode::DefineFloat32 => Some(MirInstructionData::DefineFloat32(
+                self.read_native()?,
+                self.read_native()?,
+            )),
+            MirOpCode::DefineFloat64 => Some(MirInstructionData::DefineFloat64(
+                self.read_native()?,
+                self.read_native()?,
+            )),
+            // ! Arithmetic
+            MirOpCode::AddInt32 => Some(MirInstructionData::AddInt32(
+                self.read_native()?,
+                self.read_native()?,
+                self.read_native()?,
+            )),
+            MirOpCode::SubInt32 => Some(MirInstructionData::SubInt32(
+                self.read_native()?,
+                self.read_native()?,
+                self.read_native()?,
+            )),
+            MirOpCode::MulInt32 => Some(MirInstructionData::MulInt32(
+                self.read_native()?,
+                self.read_native()?,
+                self.read_native()?,
+            )),
+            MirOpCode::DivInt32 => Some(MirInstructionData::DivInt32(
+                self.read_native()?,
+                self.read_native()?,
+                self.read_native()?,
             )),
             // ! Control flow
-            MirOpCode::ReturnValue => Some(MirInstructionData::ReturnValue(self.read_native())),
+            MirOpCode::ReturnValue => Some(MirInstructionData::ReturnValue(self.read_native()?)),
             MirOpCode::Call => {
-                let result = self.read_native();
-                let function_id = self.read_native();
-                let arguments_count: u8 = self.read_native();
-                let arguments = self.read_native_slice(arguments_count as usize);
+                let result = self.read_native()?;
+                let function_id = self.read_native()?;
+                let arguments_count: u8 = self.read_native()?;
+                let arguments = self.read_value_ids(arguments_count as usize)?;
 
                 Some(MirInstructionData::Call(result, function_id, arguments))
             }
+            MirOpCode::BindLabel => Some(MirInstructionData::BindLabel(self.read_native()?)),
+            MirOpCode::Jump => Some(MirInstructionData::Jump(self.read_native()?)),
+            MirOpCode::JumpIf => Some(MirInstructionData::JumpIf(
+                self.read_native()?,
+                self.read_native()?,
+            )),
             // ! Meta
             MirOpCode::MetaSpan => {
                 meta.span = Some(Range {
-                    start: self.read_native(),
-                    end: self.read_native(),
+                    start: self.read_native()?,
+                    end: self.read_native()?,
                 });
+                self.sticky_span = None;
                 None
             }
-        }
+            MirOpCode::MetaSpanSticky => {
+                let span = Range {
+                    start: self.read_native()?,
+                    end: self.read_native()?,
+                };
+                meta.span = Some(span.clone());
+                self.sticky_span = Some(span);
+                None
+            }
+        })
     }
-}
 
-impl<'container> Iterator for MirContainerIterator<'container> {
-    type Item = MirInstruction<'container>;
-
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Reads the next instruction along with its offset in bytes, not counting its meta data.
+    ///
+    /// Once an error is encountered, the iterator stops, because the position of the next
+    /// instruction is unknown.
+    pub(crate) fn try_next(&mut self) -> Option<Result<(usize, MirInstruction<'c>), MirError>> {
         if self.index >= self.content.data.len() {
             return None;
         }
 
-        let mut meta = MirInstructionMeta::default();
+        let mut meta = MirInstructionMeta {
+            span: self.sticky_span.clone(),
+        };
         loop {
-            if let Some(data) = unsafe { self.read_instruction(&mut meta) } {
-                return Some(MirInstruction { data, meta });
+            let offset = self.index;
+            match self.read_instruction(&mut meta) {
+                Ok(Some(data)) => return Some(Ok((offset, MirInstruction { data, meta }))),
+                Ok(None) => (),
+                Err(kind) => {
+                    self.index = self.content.data.len();
+                    return Some(Err(MirError { offset, kind }));
+                }
             }
         }
     }
 }
 
+impl<'container> Iterator for MirContainerIterator<'container> {
+    type Item = MirInstruction<'container>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next().map(|result| match result {
+            Ok((_, instruction)) => instruction,
+            Err(error) => panic!("malformed MIR: {error}"),
+        })
+    }
+}
+
 impl ColoredDisplay for MirContainer {
     fn fmt_with_color(&self, f: &mut dyn termcolor::WriteColor) -> std::io::Result<()> {
         for instruction in self.iter() {
@@ -1,6 +1,6 @@
 use std::{mem, ops::Range};
 
-use rokugo_backend_common::{FunctionId, ValueId};
+use rokugo_backend_common::{FunctionId, Label, ValueId};
 
 use super::{
     container::{MirContainer, MirContainerIterator},
@@ -10,6 +10,7 @@ use super::{
 #[derive(Debug)]
 pub struct MirEmitter {
     next_value_id: u32,
+    next_label: u32,
     content: MirContainer,
 }
 
@@ -17,6 +18,7 @@ impl MirEmitter {
     pub fn new() -> Self {
         Self {
             next_value_id: 0,
+            next_label: 0,
             content: MirContainer { data: Vec::new() },
         }
     }
@@ -49,6 +51,55 @@ impl MirEmitter {
 
         value_id
     }
+
+    /// Defines a value with assigned literal `value` which is represented by `value_id`.
+    pub fn define_float32(&mut self, value: f32) -> ValueId {
+        self.emit(MirOpCode::DefineFloat32);
+
+        // SAFETY: This function creates a new value id, and registrs it in scope.
+        let value_id = unsafe { self.next_value_id() };
+
+        self.emit_value_id(value_id);
+        self.emit_float32(value);
+
+        value_id
+    }
+
+    /// Defines a value with assigned literal `value` which is represented by `value_id`.
+    pub fn define_float64(&mut self, value: f64) -> ValueId {
+        self.emit(MirOpCode::DefineFloat64);
+
+        // SAFETY: This function creates a new value id, and registrs it in scope.
+        let value_id = unsafe { self.next_value_id() };
+
+        self.emit_value_id(value_id);
+        self.emit_float64(value);
+
+        value_id
+    }
+}
+
+/// # Arithmetic
+impl MirEmitter {
+    /// Adds `rhs` to `lhs`, both of which must be 32-bit integers. Returns the id of the result.
+    pub fn add_int32(&mut self, lhs: ValueId, rhs: ValueId) -> ValueId {
+        self.emit_binary(MirOpCode::AddInt32, lhs, rhs)
+    }
+
+    /// Subtracts `rhs` from `lhs`, both of which must be 32-bit integers. Returns the id of the result.
+    pub fn sub_int32(&mut self, lhs: ValueId, rhs: ValueId) -> ValueId {
+        self.emit_binary(MirOpCode::SubInt32, lhs, rhs)
+    }
+
+    /// Multiplies `lhs` by `rhs`, both of which must be 32-bit integers. Returns the id of the result.
+    pub fn mul_int32(&mut self, lhs: ValueId, rhs: ValueId) -> ValueId {
+        self.emit_binary(MirOpCode::MulInt32, lhs, rhs)
+    }
+
+    /// Divides `lhs` by `rhs`, both of which must be 32-bit integers. Returns the id of the result.
+    pub fn div_int32(&mut self, lhs: ValueId, rhs: ValueId) -> ValueId {
+        self.emit_binary(MirOpCode::DivInt32, lhs, rhs)
+    }
 }
 
 /// # Control flow
@@ -62,6 +113,9 @@ impl MirEmitter {
 
     /// Calls a function which is represented by `function_id` with the arguments which are represented by `arguments`.
     /// And returns the value which is returned from the called function.
+    ///
+    /// # Panics
+    /// If more than [`u8::MAX`] arguments are passed.
     pub fn call(
         &mut self,
         function_id: FunctionId,
@@ -75,31 +129,76 @@ impl MirEmitter {
         self.emit_value_id(value_id);
         self.emit_function_id(function_id);
 
-        self.emit_nat8(0);
         let position = self.content.data.len();
+        self.emit_nat8(0);
 
-        let mut count = 0;
+        let mut count = 0_usize;
         for argument in arguments {
             self.emit_value_id(argument);
             count += 1;
         }
 
-        self.content.data[position] = count;
+        self.content.data[position] = u8::try_from(count).unwrap_or_else(|_| {
+            panic!(
+                "function calls may take at most {} arguments, but {count} were passed",
+                u8::MAX
+            )
+        });
 
         value_id
     }
+
+    /// Creates a new label, which can be jumped to once it's bound with [`bind_label`][Self::bind_label].
+    pub fn create_label(&mut self) -> Label {
+        // SAFETY: Labels are plain integers, and every created label is unique.
+        let label = unsafe { mem::transmute::<u32, Label>(self.next_label) };
+        self.next_label += 1;
+        label
+    }
+
+    /// Binds `label` to the position of the next instruction. Every label must be bound exactly once.
+    pub fn bind_label(&mut self, label: Label) {
+        self.emit(MirOpCode::BindLabel);
+        self.emit_label(label);
+    }
+
+    /// Unconditionally transfers control flow to `label`.
+    pub fn jump(&mut self, label: Label) {
+        self.emit(MirOpCode::Jump);
+        self.emit_label(label);
+    }
+
+    /// Transfers control flow to `label` if the value which is represented by `condition` is true. Otherwise
+    /// continues with the next instruction.
+    pub fn jump_if(&mut self, condition: ValueId, label: Label) {
+        self.emit(MirOpCode::JumpIf);
+        self.emit_value_id(condition);
+        self.emit_label(label);
+    }
 }
 
 /// # Meta
 impl MirEmitter {
     /// Adds meta data to the next instruction, which is represented by `span` what is a range of bytes in the
     /// frontend's source code which generated that instruction. This is useful for debugging and error reporting.
+    ///
+    /// This also ends the span set by [`meta_span_sticky`][Self::meta_span_sticky], if there is one.
     pub fn meta_span(&mut self, span: Range<usize>) -> &mut Self {
         self.emit(MirOpCode::MetaSpan);
         self.emit_nat_size(span.start);
         self.emit_nat_size(span.end);
         self
     }
+
+    /// Same as [`meta_span`][Self::meta_span], but the span is attached to every following instruction, until the
+    /// next call to either this function or [`meta_span`][Self::meta_span]. This is useful for marking whole
+    /// blocks of instructions generated from a single piece of source code.
+    pub fn meta_span_sticky(&mut self, span: Range<usize>) -> &mut Self {
+        self.emit(MirOpCode::MetaSpanSticky);
+        self.emit_nat_size(span.start);
+        self.emit_nat_size(span.end);
+        self
+    }
 }
 
 /// Internal
@@ -108,11 +207,25 @@ impl MirEmitter {
     /// This function is unsafe because it returns a [`VariableId`] which does not have to be properly registered in
     /// scope, what can cause a compiler or runtime panic. The caller must ensure that the [`VariableId`] is properly.
     unsafe fn next_value_id(&mut self) -> ValueId {
-        let variable_id = mem::transmute(self.next_value_id);
+        let variable_id = mem::transmute::<u32, ValueId>(self.next_value_id);
         self.next_value_id += 1;
         variable_id
     }
 
+    /// Emits an instruction which takes two operands and returns a new value.
+    fn emit_binary(&mut self, op_code: MirOpCode, lhs: ValueId, rhs: ValueId) -> ValueId {
+        self.emit(op_code);
+
+        // SAFETY: This function creates a new value id, and registers it in scope.
+        let value_id = unsafe { self.next_value_id() };
+
+        self.emit_value_id(value_id);
+        self.emit_value_id(lhs);
+        self.emit_value_id(rhs);
+
+        value_id
+    }
+
     /// # Safety
     /// This function is unsafe because it can cause a compiler or runtime panic if the `op_code` is not properly.
     /// The caller must ensure that the `op_code` have properly values.
@@ -128,6 +241,10 @@ impl MirEmitter {
         self.content.emit_native_bytes(value_id);
     }
 
+    fn emit_label(&mut self, label: Label) {
+        self.content.emit_native_bytes(label);
+    }
+
     fn emit_nat_size(&mut self, nat_size: usize) {
         self.content.emit_native_bytes(nat_size);
     }
@@ -143,6 +260,14 @@ impl MirEmitter {
     fn emit_int32(&mut self, int32: i32) {
         self.content.emit_native_bytes(int32);
     }
+
+    fn emit_float32(&mut self, float32: f32) {
+        self.content.emit_native_bytes(float32);
+    }
+
+    fn emit_float64(&mut self, float64: f64) {
+        self.content.emit_native_bytes(float64);
+    }
 }
 
 impl Default for MirEmitter {
@@ -3,3 +3,4 @@ pub mod emitter;
 pub mod op_code;
 pub mod op_code_display;
 pub mod parameter;
+pub mod parse;
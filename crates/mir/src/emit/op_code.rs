@@ -1,7 +1,8 @@
-use rokugo_backend_common::{FunctionId, ValueId};
-use std::ops::Range;
+use rokugo_backend_common::{FunctionId, Label, ValueId};
+use std::{fmt, mem, ops::Range};
 
-#[derive(Debug)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MirOpCode {
     // ! Memory
     /// # Layout
@@ -12,6 +13,36 @@ pub enum MirOpCode {
     /// - [`ValueId`] - Returned id of this value
     /// - [`i32`] - Literal value assigned to this value
     DefineInt32,
+    /// # Layout
+    /// - [`ValueId`] - Returned id of this value
+    /// - [`f32`] - Literal value assigned to this value
+    DefineFloat32,
+    /// # Layout
+    /// - [`ValueId`] - Returned id of this value
+    /// - [`f64`] - Literal value assigned to this value
+    DefineFloat64,
+
+    // ! Arithmetic
+    /// # Layout
+    /// - [`ValueId`] - Returned id of this value
+    /// - [`ValueId`] - Id of left-hand side operand
+    /// - [`ValueId`] - Id of right-hand side operand
+    AddInt32,
+    /// # Layout
+    /// - [`ValueId`] - Returned id of this value
+    /// - [`ValueId`] - Id of left-hand side operand
+    /// - [`ValueId`] - Id of right-hand side operand
+    SubInt32,
+    /// # Layout
+    /// - [`ValueId`] - Returned id of this value
+    /// - [`ValueId`] - Id of left-hand side operand
+    /// - [`ValueId`] - Id of right-hand side operand
+    MulInt32,
+    /// # Layout
+    /// - [`ValueId`] - Returned id of this value
+    /// - [`ValueId`] - Id of left-hand side operand
+    /// - [`ValueId`] - Id of right-hand side operand
+    DivInt32,
 
     // ! Control flow
     /// # Layout
@@ -23,11 +54,53 @@ pub enum MirOpCode {
     /// - [`u8`] - Count of arguments passed to called function
     /// - [[`ValueId`]] - Arguments passed to called function
     Call,
+    /// # Layout
+    /// - [`Label`] - Label which is bound to the position of this instruction
+    BindLabel,
+    /// # Layout
+    /// - [`Label`] - Label to which control flow is transferred
+    Jump,
+    /// # Layout
+    /// - [`ValueId`] - Id of the condition value
+    /// - [`Label`] - Label to which control flow is transferred if the condition is true
+    JumpIf,
 
     // ! Meta
     /// # Layout
     /// - [`Range<usize>`]
     MetaSpan,
+    /// # Layout
+    /// - [`Range<usize>`]
+    MetaSpanSticky,
+}
+
+impl MirOpCode {
+    /// All op codes, in the order of their discriminants.
+    pub(crate) const ALL: [MirOpCode; 15] = [
+        Self::DefineNat32,
+        Self::DefineInt32,
+        Self::DefineFloat32,
+        Self::DefineFloat64,
+        Self::AddInt32,
+        Self::SubInt32,
+        Self::MulInt32,
+        Self::DivInt32,
+        Self::ReturnValue,
+        Self::Call,
+        Self::BindLabel,
+        Self::Jump,
+        Self::JumpIf,
+        Self::MetaSpan,
+        Self::MetaSpanSticky,
+    ];
+
+    /// Converts a byte back into the op code it was emitted from, if it is a valid op code.
+    pub fn from_u8(op_code: u8) -> Option<Self> {
+        Self::ALL
+            .get(usize::from(op_code))
+            .copied()
+            .filter(|&decoded| decoded as u8 == op_code)
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -41,9 +114,117 @@ pub enum MirInstructionData<'content> {
     // ! Memory
     DefineNat32(ValueId, u32),
     DefineInt32(ValueId, i32),
+    DefineFloat32(ValueId, f32),
+    DefineFloat64(ValueId, f64),
+    // ! Arithmetic
+    AddInt32(ValueId, ValueId, ValueId),
+    SubInt32(ValueId, ValueId, ValueId),
+    MulInt32(ValueId, ValueId, ValueId),
+    DivInt32(ValueId, ValueId, ValueId),
     // ! Control flow
     ReturnValue(ValueId),
-    Call(ValueId, FunctionId, &'content [ValueId]),
+    Call(ValueId, FunctionId, ValueIds<'content>),
+    BindLabel(Label),
+    Jump(Label),
+    JumpIf(ValueId, Label),
+}
+
+impl MirInstructionData<'_> {
+    /// Returns the op code this instruction is encoded with.
+    pub fn op_code(&self) -> MirOpCode {
+        match self {
+            Self::DefineNat32(_, _) => MirOpCode::DefineNat32,
+            Self::DefineInt32(_, _) => MirOpCode::DefineInt32,
+            Self::DefineFloat32(_, _) => MirOpCode::DefineFloat32,
+            Self::DefineFloat64(_, _) => MirOpCode::DefineFloat64,
+            Self::AddInt32(_, _, _) => MirOpCode::AddInt32,
+            Self::SubInt32(_, _, _) => MirOpCode::SubInt32,
+            Self::MulInt32(_, _, _) => MirOpCode::MulInt32,
+            Self::DivInt32(_, _, _) => MirOpCode::DivInt32,
+            Self::ReturnValue(_) => MirOpCode::ReturnValue,
+            Self::Call(_, _, _) => MirOpCode::Call,
+            Self::BindLabel(_) => MirOpCode::BindLabel,
+            Self::Jump(_) => MirOpCode::Jump,
+            Self::JumpIf(_, _) => MirOpCode::JumpIf,
+        }
+    }
+
+    /// Returns the value defined by this instruction, if it defines one.
+    pub fn result(&self) -> Option<ValueId> {
+        match *self {
+            Self::DefineNat32(result, _)
+            | Self::DefineInt32(result, _)
+            | Self::DefineFloat32(result, _)
+            | Self::DefineFloat64(result, _)
+            | Self::AddInt32(result, _, _)
+            | Self::SubInt32(result, _, _)
+            | Self::MulInt32(result, _, _)
+            | Self::DivInt32(result, _, _)
+            | Self::Call(result, _, _) => Some(result),
+            Self::ReturnValue(_) | Self::BindLabel(_) | Self::Jump(_) | Self::JumpIf(_, _) => None,
+        }
+    }
+
+    /// Returns the values used by this instruction, in the order they appear in it.
+    pub fn operands(&self) -> Vec<ValueId> {
+        match *self {
+            Self::DefineNat32(_, _)
+            | Self::DefineInt32(_, _)
+            | Self::DefineFloat32(_, _)
+            | Self::DefineFloat64(_, _) => vec![],
+            Self::AddInt32(_, lhs, rhs)
+            | Self::SubInt32(_, lhs, rhs)
+            | Self::MulInt32(_, lhs, rhs)
+            | Self::DivInt32(_, lhs, rhs) => vec![lhs, rhs],
+            Self::ReturnValue(value_id) | Self::JumpIf(value_id, _) => vec![value_id],
+            Self::Call(_, _, arguments) => arguments.iter().collect(),
+            Self::BindLabel(_) | Self::Jump(_) => vec![],
+        }
+    }
+}
+
+/// [`ValueId`]s stored in a MIR container. These are not necessarily aligned, and therefore cannot be borrowed as
+/// a slice of [`ValueId`]s.
+#[derive(Clone, Copy, PartialEq)]
+pub struct ValueIds<'content> {
+    bytes: &'content [u8],
+}
+
+impl<'content> ValueIds<'content> {
+    /// # Panics
+    /// If the length of `bytes` is not a multiple of the size of [`ValueId`].
+    pub(crate) fn from_bytes(bytes: &'content [u8]) -> Self {
+        assert_eq!(bytes.len() % mem::size_of::<ValueId>(), 0);
+        Self { bytes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len() / mem::size_of::<ValueId>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ValueId> + 'content {
+        self.bytes
+            .chunks_exact(mem::size_of::<ValueId>())
+            .map(bytemuck::pod_read_unaligned)
+    }
+}
+
+impl<'content> From<&'content [ValueId]> for ValueIds<'content> {
+    fn from(value_ids: &'content [ValueId]) -> Self {
+        Self {
+            bytes: bytemuck::cast_slice(value_ids),
+        }
+    }
+}
+
+impl fmt::Debug for ValueIds<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
 }
 
 #[non_exhaustive]
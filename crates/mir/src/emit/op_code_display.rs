@@ -1,6 +1,6 @@
 use std::io;
 
-use rokugo_backend_common::{FunctionId, ValueId};
+use rokugo_backend_common::{FunctionId, Label, ValueId};
 use rokugo_common::color::{ColorSpec, ColoredDisplay};
 use termcolor::{Color, WriteColor};
 
@@ -10,6 +10,7 @@ const COLOR_MEMORY: ColorSpec = ColorSpec {
     fg: Some(Color::Blue),
     intense: true,
 };
+const COLOR_ARITHMETIC: ColorSpec = COLOR_MEMORY;
 const COLOR_CONTROL_FLOW: ColorSpec = COLOR_MEMORY;
 const COLOR_META: ColorSpec = ColorSpec {
     fg: Some(Color::Black),
@@ -20,6 +21,10 @@ const COLOR_VALUE_ID: ColorSpec = ColorSpec {
     fg: Some(Color::Yellow),
     ..ColorSpec::default()
 };
+const COLOR_LABEL: ColorSpec = ColorSpec {
+    fg: Some(Color::Magenta),
+    ..ColorSpec::default()
+};
 const COLOR_FUNCTION_ID: ColorSpec = ColorSpec {
     fg: Some(Color::Green),
     ..ColorSpec::default()
@@ -45,6 +50,26 @@ fn write_value_id(f: &mut dyn WriteColor, value_id: &ValueId) -> io::Result<()>
     write!(f, "{}", value_id)
 }
 
+fn write_label(f: &mut dyn WriteColor, label: &Label) -> io::Result<()> {
+    f.set_color(&COLOR_LABEL.into())?;
+    write!(f, "{}", label)
+}
+
+fn write_binary(
+    f: &mut dyn WriteColor,
+    name: &str,
+    result: &ValueId,
+    lhs: &ValueId,
+    rhs: &ValueId,
+) -> io::Result<()> {
+    write_result(f, result)?;
+    f.set_color(&COLOR_ARITHMETIC.into())?;
+    write!(f, "{name} ")?;
+    write_value_id(f, lhs)?;
+    write!(f, " ")?;
+    write_value_id(f, rhs)
+}
+
 fn write_function_id(f: &mut dyn WriteColor, function_id: &FunctionId) -> io::Result<()> {
     f.set_color(&COLOR_FUNCTION_ID.into())?;
     write!(f, "{}", function_id)
@@ -68,6 +93,33 @@ impl ColoredDisplay for MirInstructionData<'_> {
                 f.reset()?;
                 write!(f, "{}", value)?;
             }
+            MirInstructionData::DefineFloat32(result, value) => {
+                write_result(f, result)?;
+                f.set_color(&COLOR_MEMORY.into())?;
+                write!(f, "DefineFloat32 ")?;
+                f.reset()?;
+                write!(f, "{:?}", value)?;
+            }
+            MirInstructionData::DefineFloat64(result, value) => {
+                write_result(f, result)?;
+                f.set_color(&COLOR_MEMORY.into())?;
+                write!(f, "DefineFloat64 ")?;
+                f.reset()?;
+                write!(f, "{:?}", value)?;
+            }
+            // ! Arithmetic
+            MirInstructionData::AddInt32(result, lhs, rhs) => {
+                write_binary(f, "AddInt32", result, lhs, rhs)?;
+            }
+            MirInstructionData::SubInt32(result, lhs, rhs) => {
+                write_binary(f, "SubInt32", result, lhs, rhs)?;
+            }
+            MirInstructionData::MulInt32(result, lhs, rhs) => {
+                write_binary(f, "MulInt32", result, lhs, rhs)?;
+            }
+            MirInstructionData::DivInt32(result, lhs, rhs) => {
+                write_binary(f, "DivInt32", result, lhs, rhs)?;
+            }
             // ! Control flow
             MirInstructionData::ReturnValue(value) => {
                 f.set_color(&COLOR_CONTROL_FLOW.into())?;
@@ -81,9 +133,26 @@ impl ColoredDisplay for MirInstructionData<'_> {
                 write_function_id(f, function_id)?;
                 for argument in arguments.iter() {
                     write!(f, " ")?;
-                    write_value_id(f, argument)?;
+                    write_value_id(f, &argument)?;
                 }
             }
+            MirInstructionData::BindLabel(label) => {
+                write_label(f, label)?;
+                f.reset()?;
+                write!(f, ":")?;
+            }
+            MirInstructionData::Jump(label) => {
+                f.set_color(&COLOR_CONTROL_FLOW.into())?;
+                write!(f, "Jump ")?;
+                write_label(f, label)?;
+            }
+            MirInstructionData::JumpIf(condition, label) => {
+                f.set_color(&COLOR_CONTROL_FLOW.into())?;
+                write!(f, "JumpIf ")?;
+                write_value_id(f, condition)?;
+                write!(f, " ")?;
+                write_label(f, label)?;
+            }
         }
 
         writeln!(f)?;
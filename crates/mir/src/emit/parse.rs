@@ -0,0 +1,203 @@
+use std::{error::Error, fmt, ops::Range, str::FromStr};
+
+use rokugo_backend_common::{FunctionId, Label, ValueId};
+
+use super::{container::MirContainer, op_code::MirOpCode};
+
+/// Error returned by [`parse_mir`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line on which the error occurred, counting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses MIR in the textual form produced by its [`ColoredDisplay`][rokugo_common::color::ColoredDisplay]
+/// implementation, one instruction per line:
+///
+/// ```text
+/// @MetaSpan: 0..3
+/// %0 = DefineInt32 65
+/// %1 = Call $3 %0
+/// JumpIf %1 L0
+/// L0:
+/// ReturnValue %0
+/// ```
+///
+/// Unlike with [`MirEmitter`][super::emitter::MirEmitter], value ids are taken verbatim from the text, so that
+/// the parsed MIR is identical to the one that was displayed.
+pub fn parse_mir(text: &str) -> Result<MirContainer, ParseError> {
+    let mut container = MirContainer { data: Vec::new() };
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        parse_line(&mut container, line).map_err(|message| ParseError {
+            line: index + 1,
+            message,
+        })?;
+    }
+    Ok(container)
+}
+
+fn parse_line(container: &mut MirContainer, line: &str) -> Result<(), String> {
+    if let Some(span) = line.strip_prefix("@MetaSpan:") {
+        let span = parse_span(span.trim())?;
+        container.emit_native_bytes(MirOpCode::MetaSpan as u8);
+        container.emit_native_bytes(span.start);
+        container.emit_native_bytes(span.end);
+        return Ok(());
+    }
+    if let Some(label) = line.strip_suffix(':') {
+        container.emit_native_bytes(MirOpCode::BindLabel as u8);
+        container.emit_native_bytes(parse_label(label)?);
+        return Ok(());
+    }
+
+    let (result, instruction) = match line.split_once('=') {
+        Some((result, instruction)) => (Some(parse_value_id(result.trim())?), instruction),
+        None => (None, line),
+    };
+    let mut operands = Operands(instruction.split_whitespace());
+    let name = operands.next("an instruction")?;
+    let op_code = op_code_from_name(name).ok_or_else(|| format!("unknown instruction `{name}`"))?;
+
+    let returns_value = !matches!(
+        op_code,
+        MirOpCode::ReturnValue | MirOpCode::BindLabel | MirOpCode::Jump | MirOpCode::JumpIf
+    );
+    container.emit_native_bytes(op_code as u8);
+    match (result, returns_value) {
+        (Some(result), true) => container.emit_native_bytes(result),
+        (None, true) => return Err(format!("`{name}` must be assigned to a value")),
+        (Some(_), false) => return Err(format!("`{name}` does not return a value")),
+        (None, false) => (),
+    }
+
+    match op_code {
+        // ! Memory
+        MirOpCode::DefineNat32 => container.emit_native_bytes(operands.integer::<u32>()?),
+        MirOpCode::DefineInt32 => container.emit_native_bytes(operands.integer::<i32>()?),
+        MirOpCode::DefineFloat32 => container.emit_native_bytes(operands.float::<f32>()?),
+        MirOpCode::DefineFloat64 => container.emit_native_bytes(operands.float::<f64>()?),
+        // ! Arithmetic
+        MirOpCode::AddInt32 | MirOpCode::SubInt32 | MirOpCode::MulInt32 | MirOpCode::DivInt32 => {
+            container.emit_native_bytes(operands.value_id()?);
+            container.emit_native_bytes(operands.value_id()?);
+        }
+        // ! Control flow
+        MirOpCode::ReturnValue => container.emit_native_bytes(operands.value_id()?),
+        MirOpCode::Call => {
+            container.emit_native_bytes(operands.function_id()?);
+            let arguments = operands
+                .0
+                .by_ref()
+                .map(parse_value_id)
+                .collect::<Result<Vec<_>, _>>()?;
+            let count = u8::try_from(arguments.len())
+                .map_err(|_| format!("calls may take at most {} arguments", u8::MAX))?;
+            container.emit_native_bytes(count);
+            for argument in arguments {
+                container.emit_native_bytes(argument);
+            }
+        }
+        MirOpCode::Jump => container.emit_native_bytes(operands.label()?),
+        MirOpCode::JumpIf => {
+            container.emit_native_bytes(operands.value_id()?);
+            container.emit_native_bytes(operands.label()?);
+        }
+        MirOpCode::BindLabel | MirOpCode::MetaSpan | MirOpCode::MetaSpanSticky => {
+            unreachable!("handled separately")
+        }
+    }
+
+    match operands.0.next() {
+        Some(operand) => Err(format!("unexpected operand `{operand}`")),
+        None => Ok(()),
+    }
+}
+
+fn op_code_from_name(name: &str) -> Option<MirOpCode> {
+    Some(match name {
+        "DefineNat32" => MirOpCode::DefineNat32,
+        "DefineInt32" => MirOpCode::DefineInt32,
+        "DefineFloat32" => MirOpCode::DefineFloat32,
+        "DefineFloat64" => MirOpCode::DefineFloat64,
+        "AddInt32" => MirOpCode::AddInt32,
+        "SubInt32" => MirOpCode::SubInt32,
+        "MulInt32" => MirOpCode::MulInt32,
+        "DivInt32" => MirOpCode::DivInt32,
+        "ReturnValue" => MirOpCode::ReturnValue,
+        "Call" => MirOpCode::Call,
+        "Jump" => MirOpCode::Jump,
+        "JumpIf" => MirOpCode::JumpIf,
+        _ => return None,
+    })
+}
+
+struct Operands<'a, I: Iterator<Item = &'a str>>(I);
+
+impl<'a, I: Iterator<Item = &'a str>> Operands<'a, I> {
+    fn next(&mut self, expected: &str) -> Result<&'a str, String> {
+        self.0
+            .next()
+            .ok_or_else(|| format!("expected {expected}, found end of line"))
+    }
+
+    fn integer<T: FromStr>(&mut self) -> Result<T, String> {
+        let operand = self.next("an integer")?;
+        operand
+            .parse()
+            .map_err(|_| format!("expected an integer, found `{operand}`"))
+    }
+
+    fn float<T: FromStr>(&mut self) -> Result<T, String> {
+        let operand = self.next("a float")?;
+        operand
+            .parse()
+            .map_err(|_| format!("expected a float, found `{operand}`"))
+    }
+
+    fn value_id(&mut self) -> Result<ValueId, String> {
+        parse_value_id(self.next("a value id")?)
+    }
+
+    fn function_id(&mut self) -> Result<FunctionId, String> {
+        let operand = self.next("a function id")?;
+        parse_prefixed(operand, "$", "a function id").map(bytemuck::cast::<u64, FunctionId>)
+    }
+
+    fn label(&mut self) -> Result<Label, String> {
+        parse_label(self.next("a label")?)
+    }
+}
+
+fn parse_value_id(operand: &str) -> Result<ValueId, String> {
+    parse_prefixed(operand, "%", "a value id").map(bytemuck::cast::<u32, ValueId>)
+}
+
+fn parse_label(operand: &str) -> Result<Label, String> {
+    parse_prefixed(operand, "L", "a label").map(bytemuck::cast::<u32, Label>)
+}
+
+fn parse_prefixed<T: FromStr>(operand: &str, prefix: &str, expected: &str) -> Result<T, String> {
+    operand
+        .strip_prefix(prefix)
+        .and_then(|number| number.parse().ok())
+        .ok_or_else(|| format!("expected {expected}, found `{operand}`"))
+}
+
+fn parse_span(span: &str) -> Result<Range<usize>, String> {
+    span.split_once("..")
+        .and_then(|(start, end)| Some(start.parse().ok()?..end.parse().ok()?))
+        .ok_or_else(|| format!("expected a span, found `{span}`"))
+}
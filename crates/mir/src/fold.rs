@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use rokugo_backend_common::ValueId;
+
+use crate::emit::{
+    container::MirContainer,
+    op_code::{MirInstruction, MirInstructionData},
+};
+
+/// Folds arithmetic on literals into literals, returning the optimized MIR.
+///
+/// Whenever both operands of an arithmetic instruction are known at compile time, the instruction
+/// is replaced with a `DefineInt32` of its result. Folded results are themselves known, so whole
+/// expressions such as `(2 + 3) * 4` fold down to a single define. Defines that are no longer used
+/// after folding are removed.
+///
+/// Arithmetic wraps around on overflow, the same way it does at runtime. `DefineNat32` literals are
+/// reinterpreted as 32-bit integers with the same bits. Divisions by zero, and divisions of
+/// [`i32::MIN`] by `-1`, are left for the runtime to deal with.
+pub fn fold_constants(container: &MirContainer) -> MirContainer {
+    let mut constants = HashMap::new();
+    // Uses of each value before and after folding. Defines whose uses were all folded away are
+    // dead and can be removed, but any other defines are kept as they are.
+    let mut uses_before: HashMap<ValueId, usize> = HashMap::new();
+    let mut uses_after: HashMap<ValueId, usize> = HashMap::new();
+
+    for instruction in container {
+        let data = &instruction.data;
+        let operands = data.operands();
+        for &operand in &operands {
+            *uses_before.entry(operand).or_default() += 1;
+        }
+        match evaluate(data, &constants) {
+            Some((result, value)) => {
+                constants.insert(result, value);
+            }
+            None => {
+                for operand in operands {
+                    *uses_after.entry(operand).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    let is_dead = |value_id: &ValueId| {
+        constants.contains_key(value_id)
+            && uses_before.get(value_id).is_some_and(|&uses| uses > 0)
+            && !uses_after.contains_key(value_id)
+    };
+
+    let mut folded = MirContainer { data: vec![] };
+    for instruction in container {
+        if instruction
+            .data
+            .result()
+            .is_some_and(|result| is_dead(&result))
+        {
+            continue;
+        }
+        let data = match instruction.data {
+            MirInstructionData::AddInt32(result, _, _)
+            | MirInstructionData::SubInt32(result, _, _)
+            | MirInstructionData::MulInt32(result, _, _)
+            | MirInstructionData::DivInt32(result, _, _)
+                if constants.contains_key(&result) =>
+            {
+                MirInstructionData::DefineInt32(result, constants[&result])
+            }
+            data => data,
+        };
+        folded.emit_instruction(&MirInstruction {
+            data,
+            meta: instruction.meta,
+        });
+    }
+    folded
+}
+
+/// Returns the value defined by an instruction, if it is known at compile time.
+fn evaluate(
+    data: &MirInstructionData<'_>,
+    constants: &HashMap<ValueId, i32>,
+) -> Option<(ValueId, i32)> {
+    let binary = |lhs: &ValueId, rhs: &ValueId| Some((*constants.get(lhs)?, *constants.get(rhs)?));
+    match data {
+        // ! Memory
+        MirInstructionData::DefineNat32(result, value) => Some((*result, *value as i32)),
+        MirInstructionData::DefineInt32(result, value) => Some((*result, *value)),
+        // There is no floating point arithmetic to fold yet.
+        MirInstructionData::DefineFloat32(_, _) | MirInstructionData::DefineFloat64(_, _) => None,
+        // ! Arithmetic
+        MirInstructionData::AddInt32(result, lhs, rhs) => {
+            let (lhs, rhs) = binary(lhs, rhs)?;
+            Some((*result, lhs.wrapping_add(rhs)))
+        }
+        MirInstructionData::SubInt32(result, lhs, rhs) => {
+            let (lhs, rhs) = binary(lhs, rhs)?;
+            Some((*result, lhs.wrapping_sub(rhs)))
+        }
+        MirInstructionData::MulInt32(result, lhs, rhs) => {
+            let (lhs, rhs) = binary(lhs, rhs)?;
+            Some((*result, lhs.wrapping_mul(rhs)))
+        }
+        MirInstructionData::DivInt32(result, lhs, rhs) => {
+            let (lhs, rhs) = binary(lhs, rhs)?;
+            Some((*result, lhs.checked_div(rhs)?))
+        }
+        // ! Control flow
+        MirInstructionData::ReturnValue(_)
+        | MirInstructionData::Call(_, _, _)
+        | MirInstructionData::BindLabel(_)
+        | MirInstructionData::Jump(_)
+        | MirInstructionData::JumpIf(_, _) => None,
+    }
+}
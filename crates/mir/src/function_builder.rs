@@ -18,7 +18,8 @@ impl FunctionBuilder {
     ) -> Self {
         let mut parameters = Vec::new();
         for (index, parameter) in signature_builder.parameters.into_iter().enumerate() {
-            let parameter_value_id = unsafe { mem::transmute(u32::MAX - index as u32) };
+            let parameter_value_id =
+                unsafe { mem::transmute::<u32, ValueId>(u32::MAX - index as u32) };
             parameters.push((parameter, parameter_value_id));
         }
 
@@ -1,6 +1,9 @@
 pub mod archive_builder;
+pub mod dce;
 pub mod emit;
+pub mod fold;
 pub mod function_builder;
+pub mod verify;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,60 @@
+use crate::{dce::eliminate_dead_values, emit::parse::parse_mir};
+
+fn assert_eliminates(input: &str, expected: &str) {
+    let result = eliminate_dead_values(&parse_mir(input).unwrap());
+    assert_eq!(result.data, parse_mir(expected).unwrap().data, "{result:?}");
+}
+
+#[test]
+fn unused_define() {
+    assert_eliminates(
+        "
+        %0 = DefineInt32 1
+        @MetaSpan: 0..1
+        %1 = DefineInt32 2
+        ReturnValue %1
+        ",
+        "
+        @MetaSpan: 0..1
+        %1 = DefineInt32 2
+        ReturnValue %1
+        ",
+    );
+}
+
+#[test]
+fn transitively_unused() {
+    assert_eliminates(
+        "
+        %0 = DefineInt32 1
+        %1 = DefineNat32 2
+        %2 = AddInt32 %0 %1
+        %3 = MulInt32 %2 %2
+        %4 = DefineInt32 3
+        %5 = SubInt32 %4 %0
+        ReturnValue %5
+        ",
+        "
+        %0 = DefineInt32 1
+        %4 = DefineInt32 3
+        %5 = SubInt32 %4 %0
+        ReturnValue %5
+        ",
+    );
+}
+
+#[test]
+fn side_effects_kept() {
+    let input = "
+        %0 = DefineInt32 1
+        %1 = DefineInt32 0
+        %2 = DivInt32 %0 %1
+        %3 = Call $0 %0
+        %4 = DefineInt32 1
+        JumpIf %4 L0
+        L0:
+        %5 = DefineInt32 2
+        ReturnValue %5
+        ";
+    assert_eliminates(input, input);
+}
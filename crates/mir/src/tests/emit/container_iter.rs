@@ -34,3 +34,19 @@ fn many() {
 
     assert!(iter.next().is_none());
 }
+
+#[test]
+fn sticky_span() {
+    let mut mir = MirEmitter::new();
+    let lhs = mir.meta_span_sticky(3..8).define_int32(1);
+    let rhs = mir.define_int32(2);
+    let sum = mir.meta_span(10..12).add_int32(lhs, rhs);
+    mir.return_value(sum);
+
+    let container = MirContainer::from(mir);
+    let spans: Vec<_> = container
+        .iter()
+        .map(|instruction| instruction.meta.span)
+        .collect();
+    assert_eq!(spans, [Some(3..8), Some(3..8), Some(10..12), None]);
+}
@@ -1,3 +1,5 @@
+use rokugo_backend_common::FunctionId;
+
 use crate::emit::{
     container::MirContainer,
     emitter::MirEmitter,
@@ -42,6 +44,109 @@ fn define_int32() {
     });
 }
 
+#[test]
+fn define_float32() {
+    emit_and_assert(|mir| {
+        let id = mir.define_float32(-1.5);
+        [MirInstructionData::DefineFloat32(id, -1.5)]
+    });
+}
+
+#[test]
+fn define_float64() {
+    emit_and_assert(|mir| {
+        let id = mir.define_float64(f64::MAX);
+        [MirInstructionData::DefineFloat64(id, f64::MAX)]
+    });
+}
+
+#[test]
+fn define_float_bits() {
+    // NaN never compares equal, and negative zero compares equal to positive zero, so compare the bits instead.
+    let nan32 = f32::from_bits(0x7fc0_1234);
+    let nan64 = f64::from_bits(0xfff8_0000_dead_beef);
+    let mut mir = MirEmitter::new();
+    mir.define_float32(nan32);
+    mir.define_float32(-0.0);
+    mir.define_float64(nan64);
+    mir.define_float64(-0.0);
+
+    let bits: Vec<_> = MirContainer::from(mir)
+        .iter()
+        .map(|instruction| match instruction.data {
+            MirInstructionData::DefineFloat32(_, value) => u64::from(value.to_bits()),
+            MirInstructionData::DefineFloat64(_, value) => value.to_bits(),
+            data => panic!("expected a float define, found {data:?}"),
+        })
+        .collect();
+    assert_eq!(
+        bits,
+        [
+            u64::from(nan32.to_bits()),
+            u64::from((-0.0f32).to_bits()),
+            nan64.to_bits(),
+            (-0.0f64).to_bits(),
+        ]
+    );
+}
+
+// ! Arithmetic
+#[test]
+fn add_int32() {
+    emit_and_assert(|mir| {
+        let lhs = mir.define_int32(1);
+        let rhs = mir.define_int32(2);
+        let result = mir.add_int32(lhs, rhs);
+        [
+            MirInstructionData::DefineInt32(lhs, 1),
+            MirInstructionData::DefineInt32(rhs, 2),
+            MirInstructionData::AddInt32(result, lhs, rhs),
+        ]
+    });
+}
+
+#[test]
+fn sub_int32() {
+    emit_and_assert(|mir| {
+        let lhs = mir.define_int32(-7);
+        let rhs = mir.define_int32(3);
+        let result = mir.sub_int32(lhs, rhs);
+        [
+            MirInstructionData::DefineInt32(lhs, -7),
+            MirInstructionData::DefineInt32(rhs, 3),
+            MirInstructionData::SubInt32(result, lhs, rhs),
+        ]
+    });
+}
+
+#[test]
+fn mul_int32() {
+    emit_and_assert(|mir| {
+        let lhs = mir.define_int32(6);
+        let rhs = mir.define_int32(-4);
+        let result = mir.mul_int32(lhs, rhs);
+        [
+            MirInstructionData::DefineInt32(lhs, 6),
+            MirInstructionData::DefineInt32(rhs, -4),
+            MirInstructionData::MulInt32(result, lhs, rhs),
+        ]
+    });
+}
+
+#[test]
+fn div_int32() {
+    emit_and_assert(|mir| {
+        let lhs = mir.define_int32(100);
+        let rhs = mir.define_int32(5);
+        let result = mir.div_int32(lhs, rhs);
+        [
+            MirInstructionData::DefineInt32(lhs, 100),
+            MirInstructionData::DefineInt32(rhs, 5),
+            MirInstructionData::DivInt32(result, lhs, rhs),
+        ]
+    });
+}
+
 // ! Control flow
 #[test]
 fn return_value() {
@@ -55,6 +160,76 @@ fn return_value() {
     });
 }
 
+#[test]
+fn call() {
+    emit_and_assert(|mir| {
+        let function_id = unsafe { std::mem::transmute::<u64, FunctionId>(3) };
+        let result = mir.call(function_id, []);
+        [MirInstructionData::Call(result, function_id, [][..].into())]
+    });
+
+    let mut mir = MirEmitter::new();
+    let a = mir.define_int32(1);
+    let b = mir.define_int32(2);
+    let function_id = unsafe { std::mem::transmute::<u64, FunctionId>(3) };
+    let result = mir.call(function_id, [a, b]);
+    let container = MirContainer::from(mir);
+    assert_eq!(
+        container.iter().nth(2).unwrap().data,
+        MirInstructionData::Call(result, function_id, [a, b][..].into())
+    );
+}
+
+#[test]
+fn call_max_arguments() {
+    let mut mir = MirEmitter::new();
+    let argument = mir.define_int32(1);
+    let function_id = unsafe { std::mem::transmute::<u64, FunctionId>(3) };
+    let result = mir.call(function_id, [argument; 255]);
+    let container = MirContainer::from(mir);
+    assert_eq!(
+        container.iter().nth(1).unwrap().data,
+        MirInstructionData::Call(result, function_id, [argument; 255][..].into())
+    );
+}
+
+#[test]
+#[should_panic = "function calls may take at most 255 arguments, but 256 were passed"]
+fn call_too_many_arguments() {
+    let mut mir = MirEmitter::new();
+    let argument = mir.define_int32(1);
+    let function_id = unsafe { std::mem::transmute::<u64, FunctionId>(3) };
+    mir.call(function_id, [argument; 256]);
+}
+
+#[test]
+fn forward_branch() {
+    emit_and_assert(|mir| {
+        let condition = mir.define_nat32(1);
+        let otherwise = mir.create_label();
+        let end = mir.create_label();
+        mir.jump_if(condition, otherwise);
+        let a = mir.define_int32(1);
+        mir.return_value(a);
+        mir.jump(end);
+        mir.bind_label(otherwise);
+        let b = mir.define_int32(2);
+        mir.return_value(b);
+        mir.bind_label(end);
+        [
+            MirInstructionData::DefineNat32(condition, 1),
+            MirInstructionData::JumpIf(condition, otherwise),
+            MirInstructionData::DefineInt32(a, 1),
+            MirInstructionData::ReturnValue(a),
+            MirInstructionData::Jump(end),
+            MirInstructionData::BindLabel(otherwise),
+            MirInstructionData::DefineInt32(b, 2),
+            MirInstructionData::ReturnValue(b),
+            MirInstructionData::BindLabel(end),
+        ]
+    });
+}
+
 // ! Meta
 #[test]
 fn meta_span() {
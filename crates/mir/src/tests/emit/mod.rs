@@ -1,2 +1,3 @@
 mod container_iter;
 mod emitter;
+mod parse;
@@ -0,0 +1,87 @@
+use rokugo_backend_common::FunctionId;
+use rokugo_common::color::ColoredDisplay;
+use termcolor::NoColor;
+
+use crate::emit::{
+    container::MirContainer,
+    emitter::MirEmitter,
+    parse::{parse_mir, ParseError},
+};
+
+fn display(container: &MirContainer) -> String {
+    let mut output = NoColor::new(vec![]);
+    container.fmt_with_color(&mut output).unwrap();
+    String::from_utf8(output.into_inner()).unwrap()
+}
+
+#[test]
+fn round_trip() {
+    let mut mir = MirEmitter::new();
+    let nat = mir.meta_span(0..3).define_nat32(45);
+    mir.define_float32(0.1);
+    mir.define_float64(-0.0);
+    let lhs = mir.define_int32(-65);
+    let rhs = mir.define_int32(2);
+    let sum = mir.add_int32(lhs, rhs);
+    let difference = mir.sub_int32(sum, rhs);
+    let product = mir.mul_int32(difference, lhs);
+    let quotient = mir.div_int32(product, rhs);
+    let function_id = unsafe { std::mem::transmute::<u64, FunctionId>(3) };
+    let result = mir.meta_span(4..10).call(function_id, [nat, quotient]);
+    let end = mir.create_label();
+    mir.jump_if(result, end);
+    mir.jump(end);
+    mir.bind_label(end);
+    mir.return_value(result);
+    let container = MirContainer::from(mir);
+
+    let text = display(&container);
+    let parsed = parse_mir(&text).unwrap();
+    assert_eq!(display(&parsed), text);
+    assert_eq!(parsed.data, container.data);
+}
+
+#[test]
+fn handwritten() {
+    let container = parse_mir(
+        "
+        %0 = DefineInt32 65
+        %1 = Call $3 %0
+        ReturnValue %1
+        ",
+    )
+    .unwrap();
+    assert_eq!(
+        display(&container),
+        "%0 = DefineInt32 65\n%1 = Call $3 %0\nReturnValue %1\n"
+    );
+}
+
+#[test]
+fn errors() {
+    let error = |text| parse_mir(text).unwrap_err();
+    assert_eq!(
+        error("%0 = DefineInt32 1\n%1 = Frobnicate %0"),
+        ParseError {
+            line: 2,
+            message: "unknown instruction `Frobnicate`".into(),
+        }
+    );
+    assert_eq!(
+        error("DefineInt32 1").message,
+        "`DefineInt32` must be assigned to a value"
+    );
+    assert_eq!(
+        error("%0 = ReturnValue %1").message,
+        "`ReturnValue` does not return a value"
+    );
+    assert_eq!(
+        error("ReturnValue 1").message,
+        "expected a value id, found `1`"
+    );
+    assert_eq!(
+        error("%0 = AddInt32 %1").message,
+        "expected a value id, found end of line"
+    );
+    assert_eq!(error("Jump L0 L1").message, "unexpected operand `L1`");
+}
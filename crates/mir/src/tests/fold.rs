@@ -0,0 +1,92 @@
+use crate::{emit::parse::parse_mir, fold::fold_constants};
+
+fn assert_folds(input: &str, expected: &str) {
+    let folded = fold_constants(&parse_mir(input).unwrap());
+    assert_eq!(folded.data, parse_mir(expected).unwrap().data, "{folded:?}");
+}
+
+#[test]
+fn fold_expression() {
+    // (2 + 3) * 4
+    assert_folds(
+        "
+        %0 = DefineInt32 2
+        %1 = DefineInt32 3
+        %2 = AddInt32 %0 %1
+        %3 = DefineInt32 4
+        @MetaSpan: 0..11
+        %4 = MulInt32 %2 %3
+        ReturnValue %4
+        ",
+        "
+        @MetaSpan: 0..11
+        %4 = DefineInt32 20
+        ReturnValue %4
+        ",
+    );
+}
+
+#[test]
+fn overflow_wraps() {
+    assert_folds(
+        "
+        %0 = DefineInt32 2147483647
+        %1 = DefineNat32 1
+        %2 = AddInt32 %0 %1
+        %3 = DefineInt32 -2147483648
+        %4 = DefineInt32 2
+        %5 = MulInt32 %3 %4
+        %6 = SubInt32 %3 %1
+        %7 = Call $0 %2 %5 %6
+        ReturnValue %7
+        ",
+        "
+        %2 = DefineInt32 -2147483648
+        %5 = DefineInt32 0
+        %6 = DefineInt32 2147483647
+        %7 = Call $0 %2 %5 %6
+        ReturnValue %7
+        ",
+    );
+}
+
+#[test]
+fn division_left_to_runtime() {
+    let input = "
+        %0 = DefineInt32 1
+        %1 = DefineInt32 0
+        %2 = DivInt32 %0 %1
+        %3 = DefineInt32 -2147483648
+        %4 = DefineInt32 -1
+        %5 = DivInt32 %3 %4
+        %6 = Call $0 %2 %5
+        ReturnValue %6
+        ";
+    assert_folds(input, input);
+}
+
+#[test]
+fn unknown_operands_kept() {
+    assert_folds(
+        "
+        %0 = DefineInt32 6
+        %1 = DefineInt32 3
+        %2 = DivInt32 %0 %1
+        %3 = Call $0
+        %4 = AddInt32 %2 %3
+        JumpIf %4 L0
+        L0:
+        %5 = DefineNat32 7
+        ReturnValue %2
+        ",
+        "
+        %2 = DefineInt32 2
+        %3 = Call $0
+        %4 = AddInt32 %2 %3
+        JumpIf %4 L0
+        L0:
+        %5 = DefineNat32 7
+        ReturnValue %2
+        ",
+    );
+}
@@ -0,0 +1,30 @@
+use crate::{
+    archive_builder::ArchiveBuilderRef,
+    emit::{emitter::MirEmitter, op_code::MirInstructionData},
+    function_builder::FunctionSignatureBuilder,
+};
+
+#[test]
+fn emitter_output_into_function() {
+    let archive = ArchiveBuilderRef::new();
+    let function = archive.define_function(FunctionSignatureBuilder::default());
+
+    let mut mir = MirEmitter::new();
+    let int = mir.define_int32(65);
+    mir.return_value(int);
+    function.set_or_update_mir(mir.into());
+
+    let data: Vec<_> = function
+        .mir()
+        .unwrap()
+        .iter()
+        .map(|instruction| instruction.data)
+        .collect();
+    assert_eq!(
+        data,
+        [
+            MirInstructionData::DefineInt32(int, 65),
+            MirInstructionData::ReturnValue(int),
+        ]
+    );
+}
@@ -1 +1,6 @@
+mod dce;
 mod emit;
+mod fold;
+mod function_builder;
+mod op_code_display;
+mod verify;
@@ -0,0 +1,109 @@
+use rokugo_backend_common::ValueId;
+
+use crate::{
+    emit::{container::MirContainer, emitter::MirEmitter, op_code::MirOpCode},
+    verify::{verify, verify_with_parameters, MirError, MirErrorKind},
+};
+
+fn foreign_value_id() -> ValueId {
+    let mut other = MirEmitter::new();
+    other.define_int32(0);
+    other.define_int32(0)
+}
+
+#[test]
+fn op_codes_round_trip() {
+    for op_code in MirOpCode::ALL {
+        assert_eq!(MirOpCode::from_u8(op_code as u8), Some(op_code));
+    }
+    assert_eq!(MirOpCode::from_u8(MirOpCode::ALL.len() as u8), None);
+}
+
+#[test]
+fn well_formed() {
+    let mut mir = MirEmitter::new();
+    let lhs = mir.define_int32(1);
+    let rhs = mir.meta_span(0..1).define_int32(2);
+    let sum = mir.add_int32(lhs, rhs);
+    let end = mir.create_label();
+    mir.jump_if(sum, end);
+    mir.bind_label(end);
+    mir.return_value(sum);
+
+    assert_eq!(verify(&mir.into()), Ok(()));
+}
+
+#[test]
+fn undefined_value() {
+    let undefined = foreign_value_id();
+    let mut mir = MirEmitter::new();
+    let defined = mir.define_int32(1);
+    mir.add_int32(defined, undefined);
+
+    assert_eq!(
+        verify(&mir.into()),
+        Err(vec![MirError {
+            offset: 9,
+            kind: MirErrorKind::UndefinedValue(undefined),
+        }])
+    );
+}
+
+#[test]
+fn parameters() {
+    let parameter = foreign_value_id();
+    let mut mir = MirEmitter::new();
+    mir.return_value(parameter);
+    let container = MirContainer::from(mir);
+
+    assert!(verify(&container).is_err());
+    assert_eq!(verify_with_parameters(&container, &[parameter]), Ok(()));
+}
+
+#[test]
+fn unbound_label() {
+    let mut mir = MirEmitter::new();
+    let label = mir.create_label();
+    let bound = mir.create_label();
+    mir.bind_label(bound);
+    mir.jump(label);
+
+    assert_eq!(
+        verify(&mir.into()),
+        Err(vec![MirError {
+            offset: 5,
+            kind: MirErrorKind::UnboundLabel(label),
+        }])
+    );
+}
+
+#[test]
+fn truncated() {
+    let mut mir = MirEmitter::new();
+    mir.define_int32(1);
+    let mut container = MirContainer::from(mir);
+    container.data.pop();
+
+    assert_eq!(
+        verify(&container),
+        Err(vec![MirError {
+            offset: 0,
+            kind: MirErrorKind::UnexpectedEnd,
+        }])
+    );
+}
+
+#[test]
+fn invalid_op_code() {
+    let container = MirContainer {
+        data: vec![u8::MAX],
+    };
+
+    assert_eq!(
+        verify(&container),
+        Err(vec![MirError {
+            offset: 0,
+            kind: MirErrorKind::InvalidOpCode(u8::MAX),
+        }])
+    );
+}
@@ -0,0 +1,165 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+};
+
+use rokugo_backend_common::{Label, ValueId};
+
+use crate::emit::{container::MirContainer, op_code::MirInstructionData};
+
+/// Problem found in a [`MirContainer`] by [`verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MirError {
+    /// Offset in bytes of the instruction in which the problem was found.
+    pub offset: usize,
+    pub kind: MirErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MirErrorKind {
+    /// The container ends in the middle of an instruction.
+    UnexpectedEnd,
+    /// The byte at the start of an instruction is not a valid op code.
+    InvalidOpCode(u8),
+    /// A value is used before it's defined.
+    UndefinedValue(ValueId),
+    /// A value is defined more than once.
+    RedefinedValue(ValueId),
+    /// A jump targets a label which is never bound.
+    UnboundLabel(Label),
+    /// A label is bound more than once.
+    RedefinedLabel(Label),
+}
+
+impl fmt::Display for MirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            MirErrorKind::UnexpectedEnd => write!(f, "unexpected end of MIR")?,
+            MirErrorKind::InvalidOpCode(op_code) => write!(f, "invalid op code {op_code}")?,
+            MirErrorKind::UndefinedValue(value_id) => {
+                write!(f, "value {value_id} is used before it's defined")?
+            }
+            MirErrorKind::RedefinedValue(value_id) => {
+                write!(f, "value {value_id} is defined more than once")?
+            }
+            MirErrorKind::UnboundLabel(label) => write!(f, "label {label} is never bound")?,
+            MirErrorKind::RedefinedLabel(label) => {
+                write!(f, "label {label} is bound more than once")?
+            }
+        }
+        write!(f, " (at offset {})", self.offset)
+    }
+}
+
+impl Error for MirError {}
+
+/// Checks that the MIR in `container` is well-formed, returning all problems found in it.
+///
+/// This checks that all instructions can be decoded, that every value is defined exactly once before it's used,
+/// and that every label that is jumped to is bound exactly once.
+///
+/// Since the container does not know about its function's parameters, they are not considered defined. Use
+/// [`verify_with_parameters`] to verify functions that have parameters.
+pub fn verify(container: &MirContainer) -> Result<(), Vec<MirError>> {
+    verify_with_parameters(container, &[])
+}
+
+/// Same as [`verify`], but treats `parameters` as defined before the first instruction.
+pub fn verify_with_parameters(
+    container: &MirContainer,
+    parameters: &[ValueId],
+) -> Result<(), Vec<MirError>> {
+    let mut verifier = Verifier {
+        defined: parameters.iter().copied().collect(),
+        bound: HashMap::new(),
+        jumps: vec![],
+        errors: vec![],
+    };
+
+    let mut iter = container.iter();
+    while let Some(result) = iter.try_next() {
+        match result {
+            Ok((offset, instruction)) => verifier.instruction(offset, &instruction.data),
+            Err(error) => verifier.errors.push(error),
+        }
+    }
+
+    for (offset, label) in verifier.jumps {
+        if !verifier.bound.contains_key(&label) {
+            verifier.errors.push(MirError {
+                offset,
+                kind: MirErrorKind::UnboundLabel(label),
+            });
+        }
+    }
+
+    if verifier.errors.is_empty() {
+        Ok(())
+    } else {
+        verifier.errors.sort_by_key(|error| error.offset);
+        Err(verifier.errors)
+    }
+}
+
+struct Verifier {
+    defined: HashSet<ValueId>,
+    bound: HashMap<Label, usize>,
+    jumps: Vec<(usize, Label)>,
+    errors: Vec<MirError>,
+}
+
+impl Verifier {
+    fn instruction(&mut self, offset: usize, data: &MirInstructionData<'_>) {
+        match data {
+            // ! Memory
+            MirInstructionData::DefineNat32(result, _)
+            | MirInstructionData::DefineInt32(result, _)
+            | MirInstructionData::DefineFloat32(result, _)
+            | MirInstructionData::DefineFloat64(result, _) => self.define(offset, *result),
+            // ! Arithmetic
+            MirInstructionData::AddInt32(result, lhs, rhs)
+            | MirInstructionData::SubInt32(result, lhs, rhs)
+            | MirInstructionData::MulInt32(result, lhs, rhs)
+            | MirInstructionData::DivInt32(result, lhs, rhs) => {
+                self.use_value(offset, *lhs);
+                self.use_value(offset, *rhs);
+                self.define(offset, *result);
+            }
+            // ! Control flow
+            MirInstructionData::ReturnValue(value_id) => self.use_value(offset, *value_id),
+            MirInstructionData::Call(result, _, arguments) => {
+                for argument in arguments.iter() {
+                    self.use_value(offset, argument);
+                }
+                self.define(offset, *result);
+            }
+            MirInstructionData::BindLabel(label) => {
+                if self.bound.insert(*label, offset).is_some() {
+                    self.error(offset, MirErrorKind::RedefinedLabel(*label));
+                }
+            }
+            MirInstructionData::Jump(label) => self.jumps.push((offset, *label)),
+            MirInstructionData::JumpIf(condition, label) => {
+                self.use_value(offset, *condition);
+                self.jumps.push((offset, *label));
+            }
+        }
+    }
+
+    fn define(&mut self, offset: usize, value_id: ValueId) {
+        if !self.defined.insert(value_id) {
+            self.error(offset, MirErrorKind::RedefinedValue(value_id));
+        }
+    }
+
+    fn use_value(&mut self, offset: usize, value_id: ValueId) {
+        if !self.defined.contains(&value_id) {
+            self.error(offset, MirErrorKind::UndefinedValue(value_id));
+        }
+    }
+
+    fn error(&mut self, offset: usize, kind: MirErrorKind) {
+        self.errors.push(MirError { offset, kind });
+    }
+}
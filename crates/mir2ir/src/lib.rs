@@ -1 +1,159 @@
+//! Lowering of MIR into IR.
 
+use std::{collections::HashMap, error::Error, fmt};
+
+use rokugo_backend_common::ValueId;
+use rokugo_ir::{
+    container::IrContainer,
+    register::{
+        chill::RegisterChill,
+        float::{RegisterFloat32, RegisterFloat64},
+        integer::RegisterInt32,
+        natural::RegisterNat32,
+        Register,
+    },
+};
+use rokugo_ir_generated::{
+    emit::{emitter::IrEmitter, register_allocator::RegisterDropGuard},
+    errors::register::RegisterAllocationError,
+};
+use rokugo_mir::{
+    emit::{
+        container::MirContainer,
+        op_code::{MirInstructionData, MirOpCode},
+    },
+    verify::{verify, MirError},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Problem which prevents a [`MirContainer`] from being lowered by [`lower`].
+#[derive(Debug)]
+pub enum LowerError {
+    /// The MIR is not well-formed. See [`verify`].
+    InvalidMir(Vec<MirError>),
+    /// The MIR contains an instruction which cannot be lowered yet.
+    UnsupportedInstruction(MirOpCode),
+    /// A register could not be allocated for a value.
+    RegisterAllocation(RegisterAllocationError),
+}
+
+impl fmt::Display for LowerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LowerError::InvalidMir(errors) => {
+                write!(f, "MIR is not well-formed")?;
+                for error in errors {
+                    write!(f, "\n- {error}")?;
+                }
+                Ok(())
+            }
+            LowerError::UnsupportedInstruction(op_code) => {
+                write!(f, "{op_code:?} instructions cannot be lowered to IR yet")
+            }
+            LowerError::RegisterAllocation(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for LowerError {}
+
+impl From<RegisterAllocationError> for LowerError {
+    fn from(error: RegisterAllocationError) -> Self {
+        LowerError::RegisterAllocation(error)
+    }
+}
+
+/// Register holding a lowered MIR value.
+enum ValueRegister {
+    Nat32(RegisterDropGuard<RegisterNat32>),
+    Int32(RegisterDropGuard<RegisterInt32>),
+    Float32(RegisterDropGuard<RegisterFloat32>),
+    Float64(RegisterDropGuard<RegisterFloat64>),
+}
+
+impl ValueRegister {
+    fn register(&self) -> &dyn Register {
+        match self {
+            ValueRegister::Nat32(register) => &**register,
+            ValueRegister::Int32(register) => &**register,
+            ValueRegister::Float32(register) => &**register,
+            ValueRegister::Float64(register) => &**register,
+        }
+    }
+
+    fn drop(self, ir: &mut IrEmitter) {
+        match self {
+            ValueRegister::Nat32(register) => ir.drop_register(register),
+            ValueRegister::Int32(register) => ir.drop_register(register),
+            ValueRegister::Float32(register) => ir.drop_register(register),
+            ValueRegister::Float64(register) => ir.drop_register(register),
+        };
+    }
+}
+
+/// Lowers a function's MIR into IR.
+///
+/// Register allocation is naive: every value gets a register of its own, which lives until the end
+/// of the function. Only defines and returns are supported so far; any other instruction results in
+/// [`LowerError::UnsupportedInstruction`].
+///
+/// The MIR is [verified][verify] before lowering, and since the container does not know about its
+/// function's parameters, functions with parameters cannot be lowered yet either.
+pub fn lower(mir: &MirContainer) -> Result<IrContainer, LowerError> {
+    verify(mir).map_err(LowerError::InvalidMir)?;
+
+    let mut ir = IrEmitter::new();
+    let mut registers = vec![];
+    let result = lower_instructions(mir, &mut ir, &mut registers);
+    // Registers have to be dropped even if lowering failed, since their guards panic otherwise.
+    for register in registers {
+        register.drop(&mut ir);
+    }
+    result?;
+
+    Ok(ir.into())
+}
+
+fn lower_instructions(
+    mir: &MirContainer,
+    ir: &mut IrEmitter,
+    registers: &mut Vec<ValueRegister>,
+) -> Result<(), LowerError> {
+    let mut indices: HashMap<ValueId, usize> = HashMap::new();
+    for instruction in mir {
+        let (result, register) = match instruction.data {
+            MirInstructionData::DefineNat32(result, value) => {
+                let register = ir.alloc_register_nat32(RegisterChill::default())?;
+                ir.load_nat32(&register, value);
+                (result, ValueRegister::Nat32(register))
+            }
+            MirInstructionData::DefineInt32(result, value) => {
+                let register = ir.alloc_register_int32(RegisterChill::default())?;
+                ir.load_int32(&register, value);
+                (result, ValueRegister::Int32(register))
+            }
+            MirInstructionData::DefineFloat32(result, value) => {
+                let register = ir.alloc_register_float32(RegisterChill::default())?;
+                ir.load_float32(&register, value);
+                (result, ValueRegister::Float32(register))
+            }
+            MirInstructionData::DefineFloat64(result, value) => {
+                let register = ir.alloc_register_float64(RegisterChill::default())?;
+                ir.load_float64(&register, value);
+                (result, ValueRegister::Float64(register))
+            }
+            MirInstructionData::ReturnValue(value_id) => {
+                // Verification guarantees that the value was defined before.
+                let register = &registers[indices[&value_id]];
+                ir.return_value(register.register());
+                continue;
+            }
+            ref data => return Err(LowerError::UnsupportedInstruction(data.op_code())),
+        };
+        indices.insert(result, registers.len());
+        registers.push(register);
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use rokugo_common::color::ColoredDisplay;
+use rokugo_mir::emit::{op_code::MirOpCode, parse::parse_mir};
+use termcolor::NoColor;
+
+use crate::{lower, LowerError};
+
+fn lower_to_string(mir: &str) -> Result<String, LowerError> {
+    let ir = lower(&parse_mir(mir).unwrap())?;
+    let mut output = NoColor::new(vec![]);
+    ir.fmt_with_color(&mut output).unwrap();
+    Ok(String::from_utf8(output.into_inner()).unwrap())
+}
+
+#[test]
+fn define_and_return() {
+    assert_eq!(
+        lower_to_string(
+            "
+            %0 = DefineInt32 -7
+            %1 = DefineNat32 42
+            ReturnValue %0
+            "
+        )
+        .unwrap(),
+        "AllocRegisterInt32 %r5120, chill=[]\n\
+         LoadInt32 %r5120, -7\n\
+         AllocRegisterNat32 %r1024, chill=[]\n\
+         LoadNat32 %r1024, 42\n\
+         Return %r5120\n\
+         DropRegister %r5120\n\
+         DropRegister %r1024\n"
+    );
+}
+
+#[test]
+fn define_floats() {
+    assert_eq!(
+        lower_to_string(
+            "
+            %0 = DefineFloat32 1.5
+            %1 = DefineFloat64 -0.25
+            ReturnValue %1
+            "
+        )
+        .unwrap(),
+        "AllocRegisterFloat32 %r9216, chill=[]\n\
+         LoadFloat32 %r9216, 1.5\n\
+         AllocRegisterFloat64 %r8192, chill=[]\n\
+         LoadFloat64 %r8192, -0.25\n\
+         Return %r8192\n\
+         DropRegister %r9216\n\
+         DropRegister %r8192\n"
+    );
+}
+
+#[test]
+fn unsupported_instruction() {
+    let error = lower_to_string(
+        "
+        %0 = DefineInt32 1
+        %1 = AddInt32 %0 %0
+        ReturnValue %1
+        ",
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        LowerError::UnsupportedInstruction(MirOpCode::AddInt32)
+    ));
+}
+
+#[test]
+fn invalid_mir() {
+    let error = lower_to_string("ReturnValue %0").unwrap_err();
+    assert!(matches!(error, LowerError::InvalidMir(errors) if errors.len() == 1));
+}
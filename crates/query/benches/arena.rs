@@ -0,0 +1,43 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rokugo_query::arena::Arena;
+
+fn alloc_small(n: u64) {
+    let arena = Arena::new();
+    for i in 0..n {
+        black_box(arena.alloc(i));
+    }
+}
+
+fn alloc_futures(n: u64) {
+    let arena = Arena::new();
+    for i in 0..n {
+        let future = arena.alloc_own_pinned(async move { black_box(i) });
+        black_box(future);
+    }
+}
+
+fn alloc_droppable(n: u64) {
+    let arena = Arena::new();
+    for i in 0..n {
+        black_box(arena.alloc(vec![i]));
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("arena");
+    for n in [1000, 100_000] {
+        group.bench_with_input(BenchmarkId::new("small", n), &n, |b, &n| {
+            b.iter(|| alloc_small(black_box(n)));
+        });
+        group.bench_with_input(BenchmarkId::new("futures", n), &n, |b, &n| {
+            b.iter(|| alloc_futures(black_box(n)));
+        });
+        group.bench_with_input(BenchmarkId::new("droppable", n), &n, |b, &n| {
+            b.iter(|| alloc_droppable(black_box(n)));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,48 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rokugo_query::{arena::Arena, Name, PollLoop, Query, Scheduler, Trampoline};
+
+/// Long dependency chain, where every query waits for the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Chain(u32);
+
+impl Query for Chain {
+    const NAME: Name = Name::new("Chain");
+
+    type Result = u32;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        let Chain(n) = self;
+        if n == 0 {
+            0
+        } else {
+            *scheduler.query(Chain(n - 1)).await + 1
+        }
+    }
+}
+
+fn chain_queried(n: u32) -> u32 {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    *scheduler
+        .request_and_trampoline(
+            Chain(n),
+            &Trampoline {
+                poll_loop: PollLoop::SingleThreaded,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chain");
+    for i in [100, 1000] {
+        group.bench_with_input(BenchmarkId::new("queried", i), &i, |b, &i| {
+            b.iter(|| chain_queried(black_box(i)));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
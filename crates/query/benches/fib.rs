@@ -47,12 +47,15 @@ impl Query for Fib {
 fn fib_queried(n: u32) -> u32 {
     let arena = Arena::new();
     let scheduler = arena.alloc(Scheduler::new(&arena));
-    *scheduler.request_and_trampoline(
-        Fib(n),
-        &Trampoline {
-            poll_loop: PollLoop::SingleThreaded,
-        },
-    )
+    *scheduler
+        .request_and_trampoline(
+            Fib(n),
+            &Trampoline {
+                poll_loop: PollLoop::SingleThreaded,
+                ..Default::default()
+            },
+        )
+        .unwrap()
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -4,7 +4,7 @@ use std::{
     fmt,
     future::Future,
     pin::Pin,
-    ptr::{addr_of_mut, NonNull},
+    ptr::NonNull,
     sync::atomic::{AtomicUsize, Ordering},
 };
 
@@ -15,91 +15,139 @@ use parking_lot::Mutex;
 ///
 /// Allocation arenas additionally have a lifetime parameter, which allows an arena to store
 /// references that outlives it.
+///
+/// Allocations are carved out of large chunks of memory, which are only freed once the arena is
+/// dropped. Values which need to be dropped additionally register a [`Dropper`].
 pub struct Arena {
-    // TODO: This should probably use a bump allocator rather than a vector of boxes.
-    // Allocations that require `Drop` could be stored in a linked list to avoid the overhead
-    // of reallocating the vector if it grows too big.
-    // Lots of optimization opportunities here.
-    allocs: Mutex<Vec<NonNull<Allocation<()>>>>,
+    chunks: Mutex<Chunks>,
     droppers: Mutex<Vec<Dropper>>,
     index: usize,
 }
 
-#[repr(C)]
-struct Allocation<T: Send + Sync> {
+/// Size of a regular chunk. Allocations larger than a quarter of this get a chunk of their own, so
+/// as not to waste the remaining space in the current chunk.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Bump allocator backing an [`Arena`].
+struct Chunks {
+    /// All chunks allocated so far, including the current one.
+    chunks: Vec<Chunk>,
+    /// Chunk that allocations are currently being carved out of, along with how many of its bytes
+    /// are already taken. `None` until the first allocation.
+    current: Option<(Chunk, usize)>,
+}
+
+#[derive(Clone, Copy)]
+struct Chunk {
+    ptr: NonNull<u8>,
     layout: Layout,
-    data: T,
 }
 
 struct Dropper {
-    alloc_index: usize,
-    drop_fn: unsafe fn(NonNull<Allocation<()>>),
+    ptr: NonNull<()>,
+    drop_fn: unsafe fn(NonNull<()>),
 }
 
 static ARENA_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
+impl Chunks {
+    fn alloc(&mut self, layout: Layout) -> NonNull<u8> {
+        if layout.size() == 0 {
+            // Zero-sized allocations don't need any memory, only a well-aligned pointer.
+            return NonNull::new(std::ptr::without_provenance_mut(layout.align()))
+                .expect("alignment is never zero");
+        }
+
+        if let Some((chunk, used)) = &mut self.current {
+            if let Some(ptr) = chunk.carve(used, layout) {
+                return ptr;
+            }
+        }
+
+        if layout.size() > CHUNK_SIZE / 4 {
+            let chunk = self.new_chunk(layout);
+            return chunk.ptr;
+        }
+        let mut chunk = self.new_chunk(
+            Layout::from_size_align(CHUNK_SIZE, layout.align().max(align_of::<usize>()))
+                .expect("chunk layout should be valid"),
+        );
+        let mut used = 0;
+        let ptr = chunk
+            .carve(&mut used, layout)
+            .expect("allocation should fit in a new chunk");
+        self.current = Some((chunk, used));
+        ptr
+    }
+
+    fn new_chunk(&mut self, layout: Layout) -> Chunk {
+        // SAFETY: The layout has a non-zero size, because zero-sized allocations are handled
+        // without allocating a chunk, and regular chunks have a size of `CHUNK_SIZE`.
+        let ptr = NonNull::new(unsafe { std::alloc::alloc(layout) }).expect("allocation failed");
+        let chunk = Chunk { ptr, layout };
+        self.chunks.push(chunk);
+        chunk
+    }
+}
+
+impl Chunk {
+    /// Take memory for an allocation with the given layout from the chunk, if there's enough space
+    /// left in it.
+    fn carve(&mut self, used: &mut usize, layout: Layout) -> Option<NonNull<u8>> {
+        let start = self.ptr.as_ptr() as usize + *used;
+        let padding = start.next_multiple_of(layout.align()) - start;
+        let offset = used.checked_add(padding)?;
+        let new_used = offset.checked_add(layout.size())?;
+        if new_used > self.layout.size() {
+            return None;
+        }
+        *used = new_used;
+        // SAFETY: The offset is within the chunk, as checked above.
+        Some(unsafe { self.ptr.add(offset) })
+    }
+}
+
 impl Arena {
     /// Creates a new arena.
     pub fn new() -> Self {
         Self {
-            allocs: Mutex::new(vec![]),
+            chunks: Mutex::new(Chunks {
+                chunks: vec![],
+                current: None,
+            }),
             droppers: Mutex::new(vec![]),
             index: ARENA_COUNTER.fetch_add(1, Ordering::Relaxed),
         }
     }
 
-    unsafe fn dropper<T>(ptr: NonNull<Allocation<()>>)
+    unsafe fn dropper<T>(ptr: NonNull<()>)
     where
         T: Send + Sync,
     {
-        let ptr = ptr.cast::<Allocation<T>>();
-        std::ptr::drop_in_place(addr_of_mut!((*ptr.as_ptr()).data))
+        std::ptr::drop_in_place(ptr.cast::<T>().as_ptr())
+    }
+
+    /// Allocate uninitialized memory with the given layout.
+    fn alloc_raw(&self, layout: Layout) -> NonNull<u8> {
+        self.chunks.lock().alloc(layout)
     }
 
     fn alloc_ptr<T>(&self, value: T) -> NonNull<T>
     where
         T: Send + Sync,
     {
-        let layout = Layout::new::<Allocation<T>>();
-        if layout.size() == 0 {
-            return NonNull::dangling();
-        }
+        let ptr = self.alloc_raw(Layout::new::<T>()).cast::<T>();
+        // SAFETY: `alloc_raw` returns a valid pointer, suitably aligned for `T`.
+        unsafe { std::ptr::write(ptr.as_ptr(), value) };
 
-        // SAFETY: T's layout has a non-zero size because of the above check.
-        let mut ptr = unsafe {
-            NonNull::new(std::alloc::alloc(layout))
-                .expect("allocation failed")
-                .cast::<Allocation<T>>()
-        };
-        // SAFETY: The allocated pointer is definitely valid, because we panic on
-        // allocation failure.
-        unsafe {
-            std::ptr::write(
-                ptr.as_ptr(),
-                Allocation {
-                    layout,
-                    data: value,
-                },
-            )
-        };
-
-        let erased = ptr.cast::<Allocation<()>>();
-        let alloc_index = {
-            let mut allocs = self.allocs.lock();
-            let i = allocs.len();
-            allocs.push(erased);
-            i
-        };
         if std::mem::needs_drop::<T>() {
             self.droppers.lock().push(Dropper {
-                alloc_index,
+                ptr: ptr.cast(),
                 drop_fn: Self::dropper::<T>,
             });
         }
 
-        // SAFETY: `ptr` is a valid pointer, therefore offsetting it and constructing a NonNull from
-        // it is fine.
-        unsafe { NonNull::new_unchecked(addr_of_mut!(ptr.as_mut().data)) }
+        ptr
     }
 
     /// Allocate a value in the arena and return a mutable reference to it.
@@ -114,7 +162,36 @@ impl Arena {
         unsafe { ptr.as_mut() }
     }
 
+    /// Allocate a copy of a slice in the arena and return a mutable reference to it.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice<T>(&self, items: &[T]) -> &mut [T]
+    where
+        T: Copy + Send + Sync,
+    {
+        if items.is_empty() {
+            return &mut [];
+        }
+
+        let layout = Layout::array::<T>(items.len()).expect("slice is too large");
+        let ptr = self.alloc_raw(layout).cast::<T>();
+        // SAFETY: `alloc_raw` returns a valid pointer to memory large enough to fit all the items,
+        // which cannot overlap with `items` because it was just allocated. `T: Copy`, so it does
+        // not need a dropper.
+        unsafe {
+            std::ptr::copy_nonoverlapping(items.as_ptr(), ptr.as_ptr(), items.len());
+            std::slice::from_raw_parts_mut(ptr.as_ptr(), items.len())
+        }
+    }
+
+    /// Allocate a copy of a string in the arena and return a reference to it.
+    pub fn alloc_str(&self, s: &str) -> &str {
+        let bytes = self.alloc_slice(s.as_bytes());
+        // SAFETY: The bytes were copied from a valid string.
+        unsafe { std::str::from_utf8_unchecked(bytes) }
+    }
+
     /// Same as [`alloc`][Self::alloc], but returns a pinned reference.
+    #[allow(clippy::mut_from_ref)]
     pub fn alloc_pinned<T>(&self, value: T) -> Pin<&mut T>
     where
         T: Send + Sync,
@@ -164,17 +241,59 @@ impl Arena {
         self.try_get(re).unwrap()
     }
 
+    /// Resolves an [`Own<T>`] into a shared reference without consuming it, if the [`Own<T>`] was
+    /// created in this arena. Otherwise returns [`DifferentArenaError`].
+    ///
+    /// Unlike [`Own::downgrade`], this keeps the [`Own<T>`] around, so that the allocation can be
+    /// mutated again once the returned reference is no longer used.
+    ///
+    /// The returned reference borrows the [`Own<T>`], so it cannot be used while a mutable
+    /// reference obtained through [`get_mut`][Self::get_mut] is alive:
+    ///
+    /// ```compile_fail
+    /// # use rokugo_query::arena::Arena;
+    /// let arena = Arena::new();
+    /// let mut own = arena.alloc_own(1);
+    /// let m = arena.get_mut(&mut own);
+    /// let r = arena.get_own(&own);
+    /// *m = 2;
+    /// assert_eq!(*r, 1);
+    /// ```
+    pub fn try_get_own<'b, T: ?Sized>(
+        &'b self,
+        own: &'b Own<T>,
+    ) -> Result<&'b T, DifferentArenaError> {
+        if own.arena_index == self.index {
+            // SAFETY: The `if` statement checks that the pointer inside `own` belongs to this
+            // arena, and since this arena is live, all pointers coming from it are live too.
+            // The resulting reference borrows `own`, and mutable references obtained through
+            // `try_get_mut` mutably borrow it, so the two can never be alive at the same time.
+            Ok(unsafe { own.ptr.as_ref() })
+        } else {
+            Err(DifferentArenaError)
+        }
+    }
+
+    /// Same as [`try_get_own`][Self::try_get_own], but panics on error.
+    pub fn get_own<'b, T: ?Sized>(&'b self, own: &'b Own<T>) -> &'b T {
+        self.try_get_own(own).unwrap()
+    }
+
     /// Resolves an [`Own<T>`] into a reference, if the [`Own<T>`] was created in this arena.
     /// Otherwise returns [`DifferentArenaError`].
     ///
     /// Note that this requires a mutable reference to an [`Own<T>`], which is not [`Clone`], and
-    /// therefore this cannot be used to obtain multiple mutable references to the same allocation.
-    pub fn try_get_mut<T>(&self, own: &mut Own<T>) -> Result<&mut T, DifferentArenaError> {
+    /// the returned reference keeps it mutably borrowed. Therefore this cannot be used to obtain
+    /// multiple references to the same allocation while one of them is mutable.
+    pub fn try_get_mut<'b, T>(
+        &'b self,
+        own: &'b mut Own<T>,
+    ) -> Result<&'b mut T, DifferentArenaError> {
         if own.arena_index == self.index {
             // SAFETY: The `if` statement checks that the pointer inside `own` belongs to this
             // arena, and since this arena is live, all pointers coming from it are live too.
-            // The lifetime of the resulting reference is as long as the arena's lifetime, so the
-            // reference cannot exist after the arena is dropped.
+            // The resulting reference borrows both the arena and `own` mutably, so it cannot exist
+            // after the arena is dropped, nor alongside any other reference obtained through `own`.
             Ok(unsafe { own.ptr.as_mut() })
         } else {
             Err(DifferentArenaError)
@@ -182,7 +301,7 @@ impl Arena {
     }
 
     /// Same as [`try_get_mut`][Self::try_get_mut], but panics on error.
-    pub fn get_mut<T>(&self, re: &mut Own<T>) -> &mut T {
+    pub fn get_mut<'b, T>(&'b self, re: &'b mut Own<T>) -> &'b mut T {
         self.try_get_mut(re).unwrap()
     }
 
@@ -190,21 +309,21 @@ impl Arena {
     /// this arena. Otherwise returns [`DifferentArenaError`].
     ///
     /// Note that this requires a mutable reference to an [`OwnPinned<T>`], which is not [`Clone`],
-    /// and therefore this cannot be used to obtain multiple mutable references to the same
-    /// allocation.
+    /// and the returned reference keeps it mutably borrowed. Therefore this cannot be used to
+    /// obtain multiple mutable references to the same allocation.
     ///
     /// [`OwnPinned<T>`] needs to be a separate type from [`Own<T>`] because once an allocation
     /// becomes pinned, it must not be unpinned unless it implements [`Unpin`]. This is not the case
     /// with [`Own<T>`] as the references it returns may not be pinned.
-    pub fn try_get_mut_pinned<T: ?Sized>(
-        &self,
-        own: &mut OwnPinned<T>,
-    ) -> Result<Pin<&mut T>, DifferentArenaError> {
+    pub fn try_get_mut_pinned<'b, T: ?Sized>(
+        &'b self,
+        own: &'b mut OwnPinned<T>,
+    ) -> Result<Pin<&'b mut T>, DifferentArenaError> {
         if own.arena_index == self.index {
             // SAFETY: The `if` statement checks that the pointer inside `own` belongs to this
             // arena, and since this arena is live, all pointers coming from it are live too.
-            // The lifetime of the resulting reference is as long as the arena's lifetime, so the
-            // reference cannot exist after the arena is dropped.
+            // The resulting reference borrows both the arena and `own` mutably, so it cannot exist
+            // after the arena is dropped, nor alongside any other reference obtained through `own`.
             let mut_ref = unsafe { own.ptr.as_mut() };
             Ok(unsafe { Pin::new_unchecked(mut_ref) })
         } else {
@@ -213,29 +332,26 @@ impl Arena {
     }
 
     /// Same as [`try_get_mut`][Self::try_get_mut], but panics on error.
-    pub fn get_mut_pinned<T: ?Sized>(&self, re: &mut OwnPinned<T>) -> Pin<&mut T> {
+    pub fn get_mut_pinned<'b, T: ?Sized>(&'b self, re: &'b mut OwnPinned<T>) -> Pin<&'b mut T> {
         self.try_get_mut_pinned(re).unwrap()
     }
 }
 
 impl Drop for Arena {
     fn drop(&mut self) {
-        let mut allocs = self.allocs.lock();
-        let mut droppers = self.droppers.lock();
+        let chunks = self.chunks.get_mut();
+        let droppers = self.droppers.get_mut();
 
         for dropper in droppers.drain(..) {
             // SAFETY: `Arena` has ownership of the pointer and we can safely assume it has not been
             // dropped beforehand.
-            unsafe { (dropper.drop_fn)(allocs[dropper.alloc_index]) };
+            unsafe { (dropper.drop_fn)(dropper.ptr) };
         }
 
-        for mut alloc in allocs.drain(..) {
-            // SAFETY: `Arena` has ownership of the pointer and we can safely assume it's still
+        for chunk in chunks.chunks.drain(..) {
+            // SAFETY: `Arena` has ownership of the chunk and we can safely assume it's still
             // valid at this point.
-            unsafe {
-                let layout = alloc.as_mut().layout;
-                std::alloc::dealloc(alloc.as_ptr().cast(), layout);
-            }
+            unsafe { std::alloc::dealloc(chunk.ptr.as_ptr(), chunk.layout) };
         }
     }
 }
@@ -377,6 +493,18 @@ mod tests {
         assert_eq!(arena2.try_get(shared), Err(DifferentArenaError));
     }
 
+    #[test]
+    fn own() {
+        let arena = Arena::new();
+        let mut own = arena.alloc_own(1);
+        assert_eq!(*arena.get_own(&own), 1);
+        *arena.get_mut(&mut own) = 2;
+        assert_eq!(*arena.get_own(&own), 2);
+
+        let arena2 = Arena::new();
+        assert_eq!(arena2.try_get_own(&own), Err(DifferentArenaError));
+    }
+
     #[test]
     fn self_referencing() {
         let arena = Arena::new();
@@ -397,4 +525,52 @@ mod tests {
         let re = arena.alloc(());
         assert_eq!(re, &mut ());
     }
+
+    #[test]
+    fn many_chunks() {
+        let arena = Arena::new();
+        let values: Vec<&u64> = (0..100_000).map(|i| &*arena.alloc(i)).collect();
+        assert!(values
+            .iter()
+            .copied()
+            .eq((0..100_000).collect::<Vec<_>>().iter()));
+
+        let large = arena.alloc([1_u8; 64 * 1024]);
+        assert!(large.iter().all(|&byte| byte == 1));
+        assert_eq!(*arena.alloc(1_u8), 1);
+    }
+
+    #[test]
+    fn alignment() {
+        #[repr(align(4096))]
+        struct Page(u8);
+
+        let arena = Arena::new();
+        arena.alloc(1_u8);
+        let page = arena.alloc(Page(2));
+        assert_eq!(page as *const Page as usize % 4096, 0);
+        assert_eq!(page.0, 2);
+    }
+
+    #[test]
+    fn slices() {
+        let arena = Arena::new();
+        let slice = arena.alloc_slice(&[1_u64, 2, 3]);
+        slice[0] = 4;
+        assert_eq!(slice, &[4, 2, 3]);
+
+        let empty = arena.alloc_slice::<u64>(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(arena.alloc_slice(&[(); 3]), &[(); 3]);
+    }
+
+    #[test]
+    fn strings() {
+        let arena = Arena::new();
+        let owned = String::from("hello, arena");
+        let s = arena.alloc_str(&owned);
+        drop(owned);
+        assert_eq!(s, "hello, arena");
+        assert_eq!(arena.alloc_str(""), "");
+    }
 }
@@ -0,0 +1,94 @@
+use std::{sync::OnceLock, task::Waker};
+
+use parking_lot::Mutex;
+
+/// Cell holding the result of a query, along with the tasks waiting for the result.
+pub(crate) struct QueryCell<T> {
+    value: OnceLock<Outcome<T>>,
+    waiters: Mutex<Vec<Waker>>,
+}
+
+enum Outcome<T> {
+    Computed(T),
+    Panicked,
+}
+
+/// Marker for a query which panicked instead of computing its result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Panicked;
+
+impl<T> QueryCell<T> {
+    pub(crate) fn get(&self) -> Option<&T> {
+        match self.value.get() {
+            Some(Outcome::Computed(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Whether the query finished, either by computing its result or by panicking.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.value.get().is_some()
+    }
+
+    /// Get the value, or register the waker to be woken once the value is set.
+    pub(crate) fn get_or_wait(&self, waker: &Waker) -> Option<Result<&T, Panicked>> {
+        if let Some(outcome) = self.value.get() {
+            return Some(outcome.as_result());
+        }
+
+        let mut waiters = self.waiters.lock();
+        // The value may have been set right before the lock was acquired, in which case the
+        // waiters were already woken up, and the waker would never be woken.
+        if let Some(outcome) = self.value.get() {
+            return Some(outcome.as_result());
+        }
+        if !waiters.iter().any(|waiter| waiter.will_wake(waker)) {
+            waiters.push(waker.clone());
+        }
+        None
+    }
+
+    /// Set the value and wake up all waiting tasks.
+    ///
+    /// # Panics
+    /// If the value was already set.
+    pub(crate) fn set(&self, value: T) {
+        self.finish(Outcome::Computed(value));
+    }
+
+    /// Mark the query as panicked and wake up all waiting tasks.
+    ///
+    /// # Panics
+    /// If the value was already set.
+    pub(crate) fn set_panicked(&self) {
+        self.finish(Outcome::Panicked);
+    }
+
+    fn finish(&self, outcome: Outcome<T>) {
+        self.value
+            .set(outcome)
+            .map_err(|_| ())
+            .expect("cell may only be computed once");
+        for waker in self.waiters.lock().drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Outcome<T> {
+    fn as_result(&self) -> Result<&T, Panicked> {
+        match self {
+            Outcome::Computed(value) => Ok(value),
+            Outcome::Panicked => Err(Panicked),
+        }
+    }
+}
+
+impl<T> Default for QueryCell<T> {
+    fn default() -> Self {
+        Self {
+            value: OnceLock::new(),
+            waiters: Mutex::new(vec![]),
+        }
+    }
+}
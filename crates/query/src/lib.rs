@@ -1,34 +1,57 @@
 //! Query scheduler and async runtime.
 
 pub mod arena;
+mod cell;
 mod just_about_anything;
 mod name;
 
 use std::{
-    any::type_name,
+    any::{type_name, Any},
+    cell::{Cell, RefCell},
+    collections::HashMap,
     fmt::Debug,
     future::Future,
-    hash::{BuildHasherDefault, Hash},
+    hash::{BuildHasherDefault, Hash, Hasher},
+    mem,
+    panic::{self, AssertUnwindSafe},
     pin::Pin,
-    sync::OnceLock,
-    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, OnceLock,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread,
+    time::Instant,
 };
 
 use dashmap::{DashMap, DashSet};
 use just_about_anything::JustAboutAnything;
 use parking_lot::Mutex;
-use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+use rayon::{
+    iter::{IntoParallelRefMutIterator, ParallelIterator},
+    ThreadPool, ThreadPoolBuilder,
+};
+use rokugo_diagnostic::{note, Diagnostic, NoteKind, Severity};
 use rustc_hash::FxHasher;
 
-use crate::arena::{Arena, OwnPinned};
+use crate::{
+    arena::{Arena, OwnPinned},
+    cell::{Panicked, QueryCell},
+};
 
 pub use name::Name;
 
+/// Memoized results of a single type of query.
+///
+/// Results are sticky: once computed, a query's result is kept until it's invalidated with
+/// [`Scheduler::invalidate`], unless [`Query::should_memoize`] rejects it. In that case the result is only handed to
+/// the queries that were awaiting it at the time, and the query runs again the next time it's
+/// requested.
 struct Cache<'a, Q>
 where
     Q: Query,
 {
-    cells: DashMap<Q, &'a OnceLock<Q::Result>, BuildHasherDefault<FxHasher>>,
+    cells: DashMap<Q, &'a QueryCell<Q::Result>, BuildHasherDefault<FxHasher>>,
     enqueued: DashSet<Q, BuildHasherDefault<FxHasher>>,
 }
 
@@ -43,11 +66,11 @@ where
         }
     }
 
-    fn cell(&self, arena: &'a Arena, computation: Q) -> &'a OnceLock<Q::Result> {
+    fn cell(&self, arena: &'a Arena, computation: Q) -> &'a QueryCell<Q::Result> {
         *self
             .cells
             .entry(computation.clone())
-            .or_insert_with(|| arena.alloc(OnceLock::default()))
+            .or_insert_with(|| arena.alloc(QueryCell::default()))
     }
 }
 
@@ -59,6 +82,18 @@ pub struct Scheduler<'a> {
     caches_by_type:
         DashMap<Name, &'a (dyn JustAboutAnything<'a> + Sync), BuildHasherDefault<FxHasher>>,
     erased_queue: Mutex<Vec<Box<dyn ErasedQuery>>>,
+    blocking_pool: OnceLock<ThreadPool>,
+    /// Thread pools used by the parallel polling loop, keyed by their number of threads.
+    poll_pools: Mutex<HashMap<usize, Arc<ThreadPool>>>,
+    blocking_tasks: Arc<AtomicUsize>,
+    /// Queries which awaited the result of a given query, used for invalidation.
+    ///
+    /// This is keyed by the query rather than its cell, because the cell of a query whose result
+    /// is not memoized is dropped as soon as the result is computed.
+    dependents: DashMap<Box<dyn ErasedQuery>, Vec<Dependent>, BuildHasherDefault<FxHasher>>,
+    stats: Stats,
+    /// Diagnostics about panics caught while polling queries.
+    diagnostics: Mutex<Vec<Diagnostic>>,
 
     #[cfg(debug_assertions)]
     compute_type_names: DashMap<Name, &'static str, BuildHasherDefault<FxHasher>>,
@@ -71,6 +106,12 @@ impl<'a> Scheduler<'a> {
             arena,
             caches_by_type: DashMap::default(),
             erased_queue: Mutex::new(vec![]),
+            blocking_pool: OnceLock::new(),
+            poll_pools: Mutex::new(HashMap::new()),
+            blocking_tasks: Arc::new(AtomicUsize::new(0)),
+            dependents: DashMap::default(),
+            stats: Stats::default(),
+            diagnostics: Mutex::new(vec![]),
 
             #[cfg(debug_assertions)]
             compute_type_names: DashMap::default(),
@@ -141,38 +182,332 @@ impl<'a> Scheduler<'a> {
     ///     }
     /// }
     /// ```
-    pub fn query<Q>(&self, query: Q) -> Ongoing<Q::Result>
+    pub fn query<Q>(&self, query: Q) -> Ongoing<'_, Q>
     where
         Q: Query,
     {
         let cache = self.cache::<Q>();
 
         let cell = cache.cell(self.arena, query.clone());
-        if cell.get().is_none() && cache.enqueued.insert(query.clone()) {
-            self.erased_queue.lock().push(Box::new(Some(query)));
+        if cell.is_finished() {
+            self.stats.cache_hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.stats.cache_misses.fetch_add(1, Ordering::Relaxed);
+            if cache.enqueued.insert(query.clone()) {
+                self.stats.enqueued.fetch_add(1, Ordering::Relaxed);
+                self.erased_queue.lock().push(Box::new(query.clone()));
+            }
+        }
+
+        Ongoing { cell, query }
+    }
+
+    /// Returns the result of a query if it's already computed, without requesting it.
+    ///
+    /// Unlike [`query`][Scheduler::query], this never enqueues the query, nor counts towards
+    /// [`stats`][Scheduler::stats].
+    pub fn peek<Q>(&self, query: &Q) -> Option<&'a Q::Result>
+    where
+        Q: Query,
+    {
+        let cell = *self.cache::<Q>().cells.get(query)?;
+        cell.get()
+    }
+
+    /// Request many computations at once, and await all of their results.
+    ///
+    /// All queries are added to the queue immediately, and only then awaited one by one, as
+    /// recommended in [`query`][Scheduler::query]'s documentation. The results are returned in the
+    /// same order as the queries.
+    pub fn query_all<Q, I>(&self, queries: I) -> impl Future<Output = Vec<&Q::Result>> + '_
+    where
+        Q: Query,
+        I: IntoIterator<Item = Q>,
+    {
+        let ongoing: Vec<_> = queries.into_iter().map(|query| self.query(query)).collect();
+        async move {
+            let mut results = Vec::with_capacity(ongoing.len());
+            for ongoing in ongoing {
+                results.push(ongoing.await);
+            }
+            results
+        }
+    }
+
+    /// Returns statistics about the scheduler's work so far, for profiling.
+    pub fn stats(&self) -> SchedulerStats {
+        SchedulerStats {
+            cache_hits: self.stats.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.stats.cache_misses.load(Ordering::Relaxed),
+            enqueued: self.stats.enqueued.load(Ordering::Relaxed),
+            computed: self.stats.computed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Take the diagnostics reported by the scheduler so far.
+    ///
+    /// A query which panics does not take down the whole trampoline. Instead, the panic is reported
+    /// as a [`Severity::Bug`] diagnostic here, and the query, along with all queries awaiting it,
+    /// is left without a result. Other queries are unaffected.
+    pub fn take_diagnostics(&self) -> Vec<Diagnostic> {
+        mem::take(&mut *self.diagnostics.lock())
+    }
+
+    /// Throw away the memoized result of a query, as well as results of all queries which depend
+    /// on it, either directly or indirectly.
+    ///
+    /// Requesting any of these queries again recomputes it from scratch. This is meant for
+    /// incremental compilation, where inputs to queries change between compilations. Invalidation
+    /// should not happen while the scheduler is trampolining.
+    pub fn invalidate<Q>(&self, query: &Q)
+    where
+        Q: Query,
+    {
+        self.cache::<Q>().cells.remove(query);
+        if let Some((_, dependents)) = self.dependents.remove(query as &dyn ErasedQuery) {
+            for dependent in dependents {
+                dependent.query.erased_invalidate(self);
+            }
+        }
+    }
+
+    /// Remember that `task` awaited the result of the `dependency` query.
+    fn record_dependency(&self, dependency: Box<dyn ErasedQuery>, task: &InFlight<'_>) {
+        let mut dependents = self.dependents.entry(dependency).or_default();
+        if !dependents
+            .iter()
+            .any(|dependent| dependent.cell == task.cell)
+        {
+            dependents.push(Dependent {
+                cell: task.cell,
+                query: task.query.erased_clone(),
+            });
+        }
+    }
+
+    /// Run a blocking function, such as one performing I/O, on a separate thread pool.
+    ///
+    /// Queries must not block, because that would stall the trampoline. Instead, they should
+    /// offload blocking work onto this function and await the resulting future.
+    ///
+    /// Like with [`query`][Scheduler::query], the work starts immediately, regardless of whether
+    /// the future is awaited or not.
+    ///
+    /// If the function panics, the panic is resumed in the query awaiting the result.
+    pub fn spawn_blocking<F, T>(&self, f: F) -> Blocking<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let state = Arc::new(Mutex::new(BlockingState {
+            result: None,
+            waker: None,
+        }));
+        let guard = BlockingTaskGuard::new(&self.blocking_tasks);
+        let sender = Arc::clone(&state);
+        self.blocking_pool().spawn(move || {
+            // A panic is handed over to the awaiting query, which resumes it, such that it's
+            // reported like any other panic in a query instead of tearing down the thread pool.
+            let result = panic::catch_unwind(AssertUnwindSafe(f));
+            let waker = {
+                let mut state = sender.lock();
+                state.result = Some(result);
+                state.waker.take()
+            };
+            // The task must be woken before the guard is dropped, such that the trampoline does
+            // not think it's stuck in between.
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+            drop(guard);
+        });
+        Blocking { state }
+    }
+
+    /// Thread pool with the given number of threads for the parallel polling loop. Pools are
+    /// created once and reused by later trampolines, so as not to spawn threads every time.
+    fn poll_pool(&self, num_threads: usize) -> Arc<ThreadPool> {
+        let mut pools = self.poll_pools.lock();
+        let pool = pools.entry(num_threads).or_insert_with(|| {
+            Arc::new(
+                ThreadPoolBuilder::new()
+                    .num_threads(num_threads)
+                    .build()
+                    .expect("trampoline thread pool should be created successfully"),
+            )
+        });
+        Arc::clone(pool)
+    }
+
+    fn blocking_pool(&self) -> &ThreadPool {
+        self.blocking_pool.get_or_init(|| {
+            ThreadPoolBuilder::new()
+                .thread_name(|index| format!("rokugo-query-blocking-{index}"))
+                .build()
+                .expect("blocking thread pool should be created successfully")
+        })
+    }
+
+    /// Whether any work spawned with [`spawn_blocking`][Scheduler::spawn_blocking] is still
+    /// running.
+    fn is_waiting_on_blocking(&self) -> bool {
+        self.blocking_tasks.load(Ordering::Acquire) > 0
+    }
+}
+
+/// Query which awaited the result of another query.
+struct Dependent {
+    cell: CellId,
+    query: Box<dyn ErasedQuery>,
+}
+
+/// Keeps count of blocking tasks in flight. The count is decremented on drop, after the task has
+/// handed over its result and woken the query awaiting it.
+struct BlockingTaskGuard {
+    tasks: Arc<AtomicUsize>,
+}
+
+impl BlockingTaskGuard {
+    fn new(tasks: &Arc<AtomicUsize>) -> Self {
+        tasks.fetch_add(1, Ordering::AcqRel);
+        Self {
+            tasks: Arc::clone(tasks),
         }
+    }
+}
 
-        Ongoing { cell }
+impl Drop for BlockingTaskGuard {
+    fn drop(&mut self) {
+        self.tasks.fetch_sub(1, Ordering::AcqRel);
     }
 }
 
-/// An ongoing computation of a value of type `C`.
+/// Counters backing [`SchedulerStats`].
+#[derive(Default)]
+struct Stats {
+    cache_hits: AtomicUsize,
+    cache_misses: AtomicUsize,
+    enqueued: AtomicUsize,
+    computed: AtomicUsize,
+}
+
+/// Statistics about a [`Scheduler`]'s work, returned by [`Scheduler::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SchedulerStats {
+    /// Number of queries requested whose result was already computed.
+    pub cache_hits: usize,
+    /// Number of queries requested whose result was not computed yet. This includes queries that
+    /// were already enqueued or in flight.
+    pub cache_misses: usize,
+    /// Number of queries added to the queue.
+    pub enqueued: usize,
+    /// Number of queries whose result was computed.
+    pub computed: usize,
+}
+
+/// An ongoing computation of the result of a query of type `Q`.
 ///
 /// Note that this future is fine to drop, because all computations are enqueued immediately
-/// into the [`Computer`].
+/// into the [`Scheduler`].
 #[must_use]
-pub struct Ongoing<'a, Q> {
-    cell: &'a OnceLock<Q>,
+pub struct Ongoing<'a, Q>
+where
+    Q: Query,
+{
+    cell: &'a QueryCell<Q::Result>,
+    query: Q,
 }
 
-impl<'a, Q> Future for Ongoing<'a, Q> {
-    type Output = &'a Q;
+impl<'a, Q> Future for Ongoing<'a, Q>
+where
+    Q: Query,
+{
+    type Output = &'a Q::Result;
 
-    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if let Some(value) = self.cell.get() {
-            Poll::Ready(value)
-        } else {
-            Poll::Pending
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        DEPENDENCIES.with_borrow_mut(|dependencies| dependencies.push(self.query.erased_clone()));
+        match self.cell.get_or_wait(cx.waker()) {
+            Some(Ok(value)) => Poll::Ready(value),
+            // The awaiting query cannot continue without the result, so it fails along with the
+            // awaited one. The payload lets the trampoline tell this apart from a genuine panic.
+            Some(Err(Panicked)) => panic::resume_unwind(Box::new(Panicked)),
+            None => {
+                AWAITED_CELL.set(Some(CellId::of(self.cell)));
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Identifies a query by the address of its result cell, which is unique for each query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct CellId(usize);
+
+impl CellId {
+    fn of<T>(cell: &QueryCell<T>) -> Self {
+        Self(cell as *const QueryCell<T> as usize)
+    }
+}
+
+thread_local! {
+    /// Cell of the query that the future being polled on this thread is currently waiting for.
+    static AWAITED_CELL: Cell<Option<CellId>> = const { Cell::new(None) };
+    /// All queries awaited by the future being polled on this thread.
+    static DEPENDENCIES: RefCell<Vec<Box<dyn ErasedQuery>>> = const { RefCell::new(vec![]) };
+}
+
+/// Result of polling a query's future.
+struct Polled {
+    poll: Poll<()>,
+    /// Query the future is waiting for, if it's not ready.
+    awaiting: Option<CellId>,
+    /// Queries the future awaited during the poll.
+    dependencies: Vec<Box<dyn ErasedQuery>>,
+}
+
+/// Poll a query's future, recording which queries it awaited.
+fn poll_recording_await(
+    future: Pin<&mut (dyn Future<Output = ()> + Send + '_)>,
+    waker: &Waker,
+) -> Polled {
+    AWAITED_CELL.set(None);
+    DEPENDENCIES.with_borrow_mut(|dependencies| dependencies.clear());
+    let poll = future.poll(&mut Context::from_waker(waker));
+    Polled {
+        poll,
+        awaiting: AWAITED_CELL.take(),
+        dependencies: DEPENDENCIES.take(),
+    }
+}
+
+/// Blocking work running on a separate thread pool, producing a value of type `T`.
+///
+/// See [`Scheduler::spawn_blocking`].
+#[must_use]
+pub struct Blocking<T> {
+    state: Arc<Mutex<BlockingState<T>>>,
+}
+
+struct BlockingState<T> {
+    result: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+impl<T> Future for Blocking<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock();
+        match state.result.take() {
+            Some(Ok(value)) => Poll::Ready(value),
+            Some(Err(payload)) => {
+                drop(state);
+                panic::resume_unwind(payload)
+            }
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
         }
     }
 }
@@ -187,11 +522,142 @@ pub enum PollLoop {
     Parallel,
 }
 
+/// Order in which the trampoline starts queued queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueueOrder {
+    /// Most recently requested queries are started first. This goes depth-first through the
+    /// query graph, which keeps fewer queries in flight at once.
+    #[default]
+    LastInFirstOut,
+    /// Queries are started in the order they were requested.
+    ///
+    /// Together with [`PollLoop::SingleThreaded`], this makes queries complete in the same order
+    /// every time the same set of queries is run, which in turn makes the order of diagnostics
+    /// reproducible. The trade-off is that the query graph is traversed breadth-first, which keeps
+    /// more queries in flight at once, and therefore uses more memory.
+    FirstInFirstOut,
+}
+
 /// Settings for [`Scheduler::trampoline`].
 #[derive(Debug, Clone, Default)]
 pub struct Trampoline {
     /// Which polling loop to use.
     pub poll_loop: PollLoop,
+    /// How many iterations of the polling loop may pass in a row without any query making
+    /// progress, before the trampoline gives up and panics with a diagnostic listing the queries
+    /// that are still pending.
+    ///
+    /// This is a safety net against buggy query graphs hanging the compiler forever.
+    /// `None` means there is no limit.
+    pub max_iterations: Option<usize>,
+    /// Token used for cancelling the trampoline from another thread, or from within a query.
+    pub cancellation: Option<CancellationToken>,
+    /// How many threads the [`Parallel`][PollLoop::Parallel] polling loop may use.
+    /// `None` means rayon's global thread pool is used.
+    ///
+    /// This only limits the threads used for polling queries. It does not affect threads spawned
+    /// by queries themselves, nor the thread pool used by [`Scheduler::spawn_blocking`].
+    pub num_threads: Option<usize>,
+    /// Order in which queued queries are started.
+    pub queue_order: QueueOrder,
+    /// Point in time after which the trampoline stops as if it was cancelled.
+    ///
+    /// The deadline is checked before each iteration of the polling loop, so queries being polled
+    /// when it passes are not interrupted, and the trampoline may overrun it by as long as a single
+    /// poll takes. Results computed before the deadline remain cached, so a later trampoline
+    /// resumes from where this one stopped rather than starting over.
+    pub deadline: Option<Instant>,
+}
+
+/// Token for cancelling an ongoing [`Scheduler::trampoline`].
+///
+/// Cancelling does not interrupt queries in the middle of being polled, but the trampoline will
+/// not poll nor start any more queries afterwards. Results computed before cancellation remain
+/// cached.
+///
+/// Clones of a token share their state, so cancelling one clone cancels all of them.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a new token which is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the trampoline this token was passed to.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Returns whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// Error returned by [`Scheduler::trampoline`] when it is cancelled through its
+/// [`CancellationToken`], or when its [`deadline`][Trampoline::deadline] passes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Trampoline {
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// Keeps track of how many iterations of a polling loop did not make any progress.
+struct Watchdog {
+    max_iterations: Option<usize>,
+    stalled_iterations: usize,
+}
+
+impl Watchdog {
+    fn new(trampoline: &Trampoline) -> Self {
+        Self {
+            max_iterations: trampoline.max_iterations,
+            stalled_iterations: 0,
+        }
+    }
+
+    fn check<'q>(
+        &mut self,
+        made_progress: bool,
+        pending: impl Iterator<Item = &'q dyn ErasedQuery>,
+    ) {
+        if made_progress {
+            self.stalled_iterations = 0;
+            return;
+        }
+
+        self.stalled_iterations += 1;
+        if let Some(max_iterations) = self.max_iterations {
+            if self.stalled_iterations >= max_iterations {
+                let pending: Vec<_> = pending.map(|query| format!("{query:?}")).collect();
+                let diagnostic = Severity::Bug
+                    .diagnostic(format!(
+                        "query scheduler made no progress for {max_iterations} iterations"
+                    ))
+                    .with_note(note(
+                        NoteKind::Note,
+                        format!("queries still pending: {}", pending.join(", ")),
+                    ))
+                    .with_note(note(
+                        NoteKind::Context,
+                        "this is usually caused by a query awaiting a future that never completes",
+                    ));
+                panic!("{diagnostic}");
+            }
+        }
+    }
 }
 
 /// # Scheduling functions
@@ -200,115 +666,279 @@ pub struct Trampoline {
 /// scheduler to reference the scheduler itself, but this comes at the cost of requiring the
 /// scheduler to be allocated in the [`Arena`] you pass to it.
 impl<'a> Scheduler<'a> {
-    /// Bounce in and out of scheduled tasks until all computations are done.
-    pub fn trampoline(&'a self, trampoline: &Trampoline) {
-        match trampoline.poll_loop {
-            PollLoop::SingleThreaded => self.trampoline_single_threaded(),
-            PollLoop::Parallel => self.trampoline_parallel(),
-        }
-    }
-
-    pub fn request_and_trampoline<Q>(&'a self, query: Q, trampoline: &Trampoline) -> &'a Q::Result
-    where
-        Q: Query,
-    {
-        // Dropping the future here because querying queue up a tasks, which we later trampoline
-        // back into a useful value.
-        drop(self.query(query.clone()));
-        self.trampoline(trampoline);
-        self.cache()
-            .cell(self.arena, query)
-            .get()
-            .expect("query should have computed a result into the cache")
-    }
-
-    fn trampoline_single_threaded(&'a self) {
-        let mut future_queue: Vec<OwnPinned<dyn Future<Output = ()> + Send>> = vec![];
+    /// Bounce in and out of scheduled tasks until all computations are done, or until the
+    /// trampoline is cancelled or its deadline passes.
+    ///
+    /// Queries that were still in flight when the trampoline got cancelled are put back into the
+    /// queue, such that a later trampoline restarts them.
+    pub fn trampoline(&'a self, trampoline: &Trampoline) -> Result<(), Cancelled> {
+        let mut tasks = Tasks::default();
+        let mut watchdog = Watchdog::new(trampoline);
+        let thread_pool = match (trampoline.poll_loop, trampoline.num_threads) {
+            (PollLoop::Parallel, Some(num_threads)) => Some(self.poll_pool(num_threads)),
+            _ => None,
+        };
         loop {
-            while let Some(mut erased_computation) = self.erased_queue.lock().pop() {
+            if trampoline.is_cancelled() {
+                self.requeue(tasks.in_flight.into_iter().flatten().map(|task| task.query));
+                return Err(Cancelled);
+            }
+
+            // Starting a query does not poll it, so no queries are requested while the queue is
+            // being drained.
+            let mut queued = mem::take(&mut *self.erased_queue.lock());
+            if trampoline.queue_order == QueueOrder::LastInFirstOut {
+                queued.reverse();
+            }
+            let mut made_progress = !queued.is_empty();
+            for erased_computation in queued {
                 let future = erased_computation.erased_query(self);
-                future_queue.push(future);
+                tasks.spawn(self, erased_computation, future);
             }
 
-            let mut i = 0;
-            while i < future_queue.len() {
-                let mut pinned = self.arena.get_mut_pinned(&mut future_queue[i]);
-                let poll = pinned
+            // Only tasks which were woken up are polled, because others are guaranteed to still be
+            // waiting for something.
+            let mut woken = tasks.take_woken();
+            let poll = |task: &mut InFlight<'a>| {
+                let future = task
+                    .future
                     .as_mut()
-                    .poll(&mut Context::from_waker(&noop_waker()));
-                match poll {
-                    Poll::Pending => (),
-                    Poll::Ready(()) => {
-                        future_queue.swap_remove(i);
-                        continue;
+                    .expect("finished tasks are never polled");
+                let pinned = self.arena.get_mut_pinned(future);
+                let polled = match panic::catch_unwind(AssertUnwindSafe(|| {
+                    poll_recording_await(pinned, &task.waker)
+                })) {
+                    Ok(polled) => polled,
+                    Err(payload) => {
+                        task.future = None;
+                        task.query.erased_panicked(self, payload);
+                        return;
                     }
+                };
+                task.awaiting = polled.awaiting;
+                for dependency in polled.dependencies {
+                    self.record_dependency(dependency, task);
+                }
+                if polled.poll.is_ready() {
+                    task.future = None;
+                }
+            };
+            match (trampoline.poll_loop, &thread_pool) {
+                (PollLoop::SingleThreaded, _) => woken.iter_mut().for_each(poll),
+                (PollLoop::Parallel, None) => woken.par_iter_mut().for_each(poll),
+                (PollLoop::Parallel, Some(thread_pool)) => {
+                    thread_pool.install(|| woken.par_iter_mut().for_each(poll))
+                }
+            }
+            for task in woken {
+                if task.future.is_none() {
+                    tasks.finish(task);
+                    made_progress = true;
+                } else {
+                    tasks.put_back(task);
                 }
-                i += 1;
             }
 
-            if future_queue.is_empty() {
-                break;
+            if tasks.is_empty() && self.erased_queue.lock().is_empty() {
+                return Ok(());
+            }
+
+            if !made_progress {
+                let in_flight: Vec<_> = tasks.in_flight.iter().flatten().collect();
+                if let Some(cycle) = find_cycle(&in_flight) {
+                    report_cycle(&cycle);
+                }
+            }
+
+            // Queries waiting for blocking work to finish are not stuck, even though they cannot
+            // make any progress until it does.
+            let waiting_on_blocking = self.is_waiting_on_blocking();
+            if !made_progress && waiting_on_blocking {
+                thread::yield_now();
             }
+            watchdog.check(
+                made_progress || waiting_on_blocking,
+                tasks
+                    .in_flight
+                    .iter()
+                    .flatten()
+                    .map(|task| task.query.as_ref()),
+            );
         }
     }
 
-    fn trampoline_parallel(&'a self) {
-        let mut future_queue: Vec<Option<OwnPinned<dyn Future<Output = ()> + Send>>> = vec![];
-        loop {
-            while let Some(mut erased_computation) = self.erased_queue.lock().pop() {
-                let future = erased_computation.erased_query(self);
-                future_queue.push(Some(future));
+    /// Request a query and trampoline until it's computed, returning its result.
+    ///
+    /// # Panics
+    /// If the query, or any query it awaited, panicked. The panic is described by the diagnostics
+    /// in [`take_diagnostics`][Scheduler::take_diagnostics].
+    pub fn request_and_trampoline<Q>(
+        &'a self,
+        query: Q,
+        trampoline: &Trampoline,
+    ) -> Result<&'a Q::Result, Cancelled>
+    where
+        Q: Query,
+    {
+        // Not awaiting the future here because querying queues up a task, which we later
+        // trampoline back into a useful value. The cell is held onto, because results which are
+        // not memoized are removed from the cache once computed.
+        let ongoing = self.query(query);
+        self.trampoline(trampoline)?;
+        match ongoing.cell.get() {
+            Some(result) => Ok(result),
+            None if ongoing.cell.is_finished() => {
+                panic!("requested query {:?} panicked", Q::NAME)
             }
+            None => panic!("query should have computed a result into the cache"),
+        }
+    }
 
-            future_queue.par_iter_mut().for_each(|future| {
-                let mut pinned = self.arena.get_mut_pinned(
-                    future
-                        .as_mut()
-                        .expect("future queue must be cleared of None"),
-                );
-                let poll = pinned
-                    .as_mut()
-                    .poll(&mut Context::from_waker(&noop_waker()));
-                match poll {
-                    Poll::Pending => (),
-                    Poll::Ready(()) => {
-                        *future = None;
-                    }
-                }
-            });
+    /// Put queries whose futures got dropped because of cancellation back into the queue.
+    fn requeue(&self, queries: impl Iterator<Item = Box<dyn ErasedQuery>>) {
+        self.erased_queue.lock().extend(queries);
+    }
+}
 
-            let mut i = 0;
-            while i < future_queue.len() {
-                if future_queue[i].is_none() {
-                    future_queue.swap_remove(i);
-                } else {
-                    i += 1;
-                }
+/// Tasks driven by the trampoline.
+#[derive(Default)]
+struct Tasks<'a> {
+    /// Tasks indexed by their ID. Slots of finished tasks are reused for new tasks.
+    in_flight: Vec<Option<InFlight<'a>>>,
+    free: Vec<usize>,
+    woken: Arc<Mutex<Vec<usize>>>,
+}
+
+impl<'a> Tasks<'a> {
+    fn spawn(
+        &mut self,
+        scheduler: &'a Scheduler<'a>,
+        query: Box<dyn ErasedQuery>,
+        future: ErasedFuture<'a>,
+    ) {
+        let id = self.free.pop().unwrap_or(self.in_flight.len());
+        let task = InFlight {
+            id,
+            cell: query.erased_cell(scheduler),
+            query,
+            awaiting: None,
+            waker: Waker::from(Arc::new(TaskWaker {
+                id,
+                woken: Arc::clone(&self.woken),
+            })),
+            future: Some(future),
+        };
+        if id == self.in_flight.len() {
+            self.in_flight.push(Some(task));
+        } else {
+            self.in_flight[id] = Some(task);
+        }
+        // New tasks have to be polled at least once to start them.
+        self.woken.lock().push(id);
+    }
+
+    /// Take woken up tasks out of the list. They have to be put back if they're not finished.
+    fn take_woken(&mut self) -> Vec<InFlight<'a>> {
+        let woken = mem::take(&mut *self.woken.lock());
+        let mut tasks = vec![];
+        for id in woken {
+            // Tasks can be woken more than once, and wakers of finished tasks may still be
+            // around; in both cases the slot is empty.
+            if let Some(task) = self.in_flight[id].take() {
+                tasks.push(task);
             }
+        }
+        tasks
+    }
+
+    fn put_back(&mut self, task: InFlight<'a>) {
+        let id = task.id;
+        self.in_flight[id] = Some(task);
+    }
+
+    fn finish(&mut self, task: InFlight<'a>) {
+        self.free.push(task.id);
+    }
 
-            if future_queue.is_empty() {
+    fn is_empty(&self) -> bool {
+        self.free.len() == self.in_flight.len()
+    }
+}
+
+/// Query whose future is being driven by the trampoline.
+struct InFlight<'a> {
+    id: usize,
+    query: Box<dyn ErasedQuery>,
+    cell: CellId,
+    /// Query this one was waiting for the last time it was polled.
+    awaiting: Option<CellId>,
+    waker: Waker,
+    /// `None` once the future has finished.
+    future: Option<ErasedFuture<'a>>,
+}
+
+/// Wakes up a task by adding it to the trampoline's list of tasks to poll.
+struct TaskWaker {
+    id: usize,
+    woken: Arc<Mutex<Vec<usize>>>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.lock().push(self.id);
+    }
+}
+
+/// Find queries that await each other in a cycle, which means none of them can ever finish.
+fn find_cycle<'q>(future_queue: &[&'q InFlight<'_>]) -> Option<Vec<&'q dyn ErasedQuery>> {
+    let by_cell: HashMap<CellId, usize> = future_queue
+        .iter()
+        .enumerate()
+        .map(|(i, in_flight)| (in_flight.cell, i))
+        .collect();
+
+    // Each query awaits at most one other query at a time, so following the chain of awaited
+    // queries either reaches a query that isn't waiting for another one, or loops back.
+    let mut visited = vec![false; future_queue.len()];
+    for start in 0..future_queue.len() {
+        let mut path: Vec<usize> = vec![];
+        let mut current = Some(start);
+        while let Some(i) = current {
+            if let Some(cycle_start) = path.iter().position(|&visited| visited == i) {
+                return Some(
+                    path[cycle_start..]
+                        .iter()
+                        .map(|&i| future_queue[i].query.as_ref())
+                        .collect(),
+                );
+            }
+            if visited[i] {
                 break;
             }
+            visited[i] = true;
+            path.push(i);
+            current = future_queue[i]
+                .awaiting
+                .and_then(|cell| by_cell.get(&cell).copied());
         }
     }
+    None
 }
 
-// Stable copy of Waker::noop.
-fn noop_waker() -> Waker {
-    const VTABLE: RawWakerVTable = RawWakerVTable::new(
-        // Cloning just returns a new no-op raw waker
-        |_| RAW,
-        // `wake` does nothing
-        |_| {},
-        // `wake_by_ref` does nothing
-        |_| {},
-        // Dropping does nothing as we don't allocate anything
-        |_| {},
-    );
-    const RAW: RawWaker = RawWaker::new(std::ptr::null(), &VTABLE);
-
-    // SAFETY: All methods are noops.
-    unsafe { Waker::from_raw(RAW) }
+fn report_cycle(cycle: &[&dyn ErasedQuery]) -> ! {
+    let mut queries: Vec<_> = cycle.iter().map(|query| format!("{query:?}")).collect();
+    queries.push(queries[0].clone());
+    let diagnostic = Severity::Bug
+        .diagnostic("queries await each other in a cycle")
+        .with_note(note(
+            NoteKind::Note,
+            format!("cycle: {}", queries.join(" -> ")),
+        ));
+    panic!("{diagnostic}");
 }
 
 /// Represents a computation type.
@@ -328,37 +958,147 @@ pub trait Query: 'static + Clone + Debug + Eq + Hash + Send + Sync {
         self,
         scheduler: &'a Scheduler<'_>,
     ) -> impl Future<Output = Self::Result> + Send + Sync + 'a;
+
+    /// Whether the result should be memoized, such that later requests for this query reuse it.
+    ///
+    /// All results are memoized by default. Queries that can fail, and whose failure may go away
+    /// when they're retried (for instance because a file they read got fixed), should override
+    /// this to only memoize successful results:
+    ///
+    /// ```
+    /// # use rokugo_query::*;
+    /// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    /// struct ReadFile(String);
+    ///
+    /// impl Query for ReadFile {
+    ///     const NAME: Name = Name::new("ReadFile");
+    ///
+    ///     type Result = Result<String, String>;
+    ///
+    ///     async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+    ///         scheduler
+    ///             .spawn_blocking(move || std::fs::read_to_string(&self.0))
+    ///             .await
+    ///             .map_err(|error| error.to_string())
+    ///     }
+    ///
+    ///     fn should_memoize(result: &Self::Result) -> bool {
+    ///         result.is_ok()
+    ///     }
+    /// }
+    /// ```
+    fn should_memoize(result: &Self::Result) -> bool {
+        let _ = result;
+        true
+    }
 }
 
+type ErasedFuture<'a> = OwnPinned<dyn Future<Output = ()> + Send + 'a>;
+
 // Object-safe version of `Compute`.
-trait ErasedQuery: Send {
-    fn erased_query<'a>(
-        &mut self,
-        scheduler: &'a Scheduler<'a>,
-    ) -> OwnPinned<dyn Future<Output = ()> + Send + 'a>;
+trait ErasedQuery: Debug + Send + Sync {
+    fn erased_query<'a>(&self, scheduler: &'a Scheduler<'a>) -> ErasedFuture<'a>;
+    fn erased_cell<'a>(&self, scheduler: &'a Scheduler<'a>) -> CellId;
+    fn erased_invalidate(&self, scheduler: &Scheduler<'_>);
+    fn erased_clone(&self) -> Box<dyn ErasedQuery>;
+    fn erased_panicked(&self, scheduler: &Scheduler<'_>, payload: Box<dyn Any + Send>);
+    fn erased_eq(&self, other: &dyn ErasedQuery) -> bool;
+    fn erased_hash(&self, state: &mut dyn Hasher);
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl PartialEq for dyn ErasedQuery {
+    fn eq(&self, other: &Self) -> bool {
+        self.erased_eq(other)
+    }
+}
+
+impl Eq for dyn ErasedQuery {}
+
+impl Hash for dyn ErasedQuery {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.erased_hash(state)
+    }
 }
 
-impl<Q> ErasedQuery for Option<Q>
+impl<Q> ErasedQuery for Q
 where
     Q: Query,
 {
-    fn erased_query<'a>(
-        &mut self,
-        scheduler: &'a Scheduler<'a>,
-    ) -> OwnPinned<dyn Future<Output = ()> + Send + 'a> {
-        let query = self.take().expect("erased_query must only be called once");
+    fn erased_query<'a>(&self, scheduler: &'a Scheduler<'a>) -> ErasedFuture<'a> {
+        let query = self.clone();
         let cache = scheduler.cache();
         let cell = cache.cell(scheduler.arena, query.clone());
         scheduler
             .arena
             .alloc_own_pinned(async move {
                 let cache = scheduler.cache::<Q>();
-                let future = query.clone().run(scheduler).await;
-                cell.set(future)
-                    .map_err(|_| ())
-                    .expect("cell may only be computed once");
+                let result = query.clone().run(scheduler).await;
+                let memoize = Q::should_memoize(&result);
+                cell.set(result);
+                scheduler.stats.computed.fetch_add(1, Ordering::Relaxed);
+                // The query must leave the queue before its cell is removed. Otherwise a request
+                // in between would create a fresh cell, but not enqueue it, as the query would
+                // still appear to be enqueued, and the fresh cell would never be filled in.
                 cache.enqueued.remove(&query);
+                if !memoize {
+                    cache.cells.remove(&query);
+                }
             })
             .as_dyn_send_future()
     }
+
+    fn erased_cell<'a>(&self, scheduler: &'a Scheduler<'a>) -> CellId {
+        CellId::of(scheduler.cache::<Q>().cell(scheduler.arena, self.clone()))
+    }
+
+    fn erased_invalidate(&self, scheduler: &Scheduler<'_>) {
+        scheduler.invalidate(self);
+    }
+
+    fn erased_clone(&self) -> Box<dyn ErasedQuery> {
+        Box::new(self.clone())
+    }
+
+    fn erased_eq(&self, other: &dyn ErasedQuery) -> bool {
+        other.as_any().downcast_ref::<Q>() == Some(self)
+    }
+
+    fn erased_hash(&self, mut state: &mut dyn Hasher) {
+        Q::NAME.hash(&mut state);
+        self.hash(&mut state);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn erased_panicked(&self, scheduler: &Scheduler<'_>, payload: Box<dyn Any + Send>) {
+        let cache = scheduler.cache::<Q>();
+        cache.cell(scheduler.arena, self.clone()).set_panicked();
+        cache.enqueued.remove(self);
+
+        // Queries which panicked because a query they awaited panicked are not reported, as the
+        // original panic already is.
+        if !payload.is::<Panicked>() {
+            let diagnostic = Severity::Bug
+                .diagnostic(format!("query {:?} panicked", Q::NAME))
+                .with_note(note(
+                    NoteKind::Note,
+                    format!("panic message: {}", panic_message(payload.as_ref())),
+                ))
+                .with_note(note(NoteKind::Context, format!("while computing {self:?}")));
+            scheduler.diagnostics.lock().push(diagnostic);
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "<non-string panic payload>"
+    }
 }
@@ -56,7 +56,7 @@ impl Name {
         }
 
         assert!(size_of::<usize>() <= 8);
-        while bytes.len() >= size_of::<usize>() {
+        while bytes.len() - i >= size_of::<usize>() {
             add_to_hash!(u64::from_le_bytes([
                 bytes[i],
                 bytes[1 + i],
@@ -69,17 +69,17 @@ impl Name {
             ]));
             i += size_of::<usize>();
         }
-        if (size_of::<usize>() > 4) && (bytes.len() >= 4) {
+        if (size_of::<usize>() > 4) && (bytes.len() - i >= 4) {
             add_to_hash!(
                 u32::from_ne_bytes([bytes[i], bytes[1 + i], bytes[2 + i], bytes[3 + i]]) as u64
             );
             i += 4;
         }
-        if (size_of::<usize>() > 2) && bytes.len() >= 2 {
+        if (size_of::<usize>() > 2) && bytes.len() - i >= 2 {
             add_to_hash!(u16::from_ne_bytes([bytes[i], bytes[1 + i]]) as u64);
             i += 2;
         }
-        if (size_of::<usize>() > 1) && !bytes.is_empty() {
+        if (size_of::<usize>() > 1) && bytes.len() - i >= 1 {
             add_to_hash!(bytes[i] as u64);
         }
 
@@ -0,0 +1,108 @@
+use std::{thread, time::Duration};
+
+use rokugo_diagnostic::Severity;
+use rokugo_query::{arena::Arena, Name, PollLoop, Query, Scheduler, Trampoline};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ReadNumber;
+
+impl Query for ReadNumber {
+    const NAME: Name = Name::new("ReadNumber");
+
+    type Result = u32;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        scheduler
+            .spawn_blocking(|| {
+                thread::sleep(Duration::from_millis(50));
+                41
+            })
+            .await
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct AddOne;
+
+impl Query for AddOne {
+    const NAME: Name = Name::new("AddOne");
+
+    type Result = u32;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        scheduler.query(ReadNumber).await + 1
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ReadPanicking;
+
+impl Query for ReadPanicking {
+    const NAME: Name = Name::new("ReadPanicking");
+
+    type Result = u32;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        scheduler
+            .spawn_blocking(|| panic!("deliberate panic"))
+            .await
+    }
+}
+
+fn run(poll_loop: PollLoop) -> u32 {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    *scheduler
+        .request_and_trampoline(
+            AddOne,
+            &Trampoline {
+                poll_loop,
+                // Waiting on blocking work must not be mistaken for being stuck.
+                max_iterations: Some(3),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn blocking_single_threaded() {
+    assert_eq!(run(PollLoop::SingleThreaded), 42);
+}
+
+#[test]
+fn blocking_parallel() {
+    assert_eq!(run(PollLoop::Parallel), 42);
+}
+
+fn panics_are_reported(poll_loop: PollLoop) {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    let _read_panicking = scheduler.query(ReadPanicking);
+    assert_eq!(
+        scheduler.trampoline(&Trampoline {
+            poll_loop,
+            ..Default::default()
+        }),
+        Ok(())
+    );
+
+    let diagnostics = scheduler.take_diagnostics();
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:#?}");
+    assert_eq!(diagnostics[0].severity, Severity::Bug);
+    assert!(diagnostics[0].message.contains("ReadPanicking"));
+    assert!(diagnostics[0]
+        .notes
+        .iter()
+        .any(|note| note.message.contains("deliberate panic")));
+}
+
+#[test]
+fn panics_are_reported_single_threaded() {
+    panics_are_reported(PollLoop::SingleThreaded);
+}
+
+#[test]
+fn panics_are_reported_parallel() {
+    panics_are_reported(PollLoop::Parallel);
+}
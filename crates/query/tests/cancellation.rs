@@ -0,0 +1,82 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    OnceLock,
+};
+
+use rokugo_query::{
+    arena::Arena, CancellationToken, Cancelled, Name, PollLoop, Query, Scheduler, Trampoline,
+};
+
+static CANCELLATION: OnceLock<CancellationToken> = OnceLock::new();
+static LEAF_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+fn cancellation() -> &'static CancellationToken {
+    CANCELLATION.get_or_init(CancellationToken::new)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Leaf;
+
+impl Query for Leaf {
+    const NAME: Name = Name::new("Leaf");
+
+    type Result = u32;
+
+    async fn run(self, _scheduler: &Scheduler<'_>) -> Self::Result {
+        LEAF_RUNS.fetch_add(1, Ordering::SeqCst);
+        1
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Cancel;
+
+impl Query for Cancel {
+    const NAME: Name = Name::new("Cancel");
+
+    type Result = u32;
+
+    async fn run(self, _scheduler: &Scheduler<'_>) -> Self::Result {
+        cancellation().cancel();
+        2
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Root;
+
+impl Query for Root {
+    const NAME: Name = Name::new("Root");
+
+    type Result = u32;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        let leaf = *scheduler.query(Leaf).await;
+        let cancel = *scheduler.query(Cancel).await;
+        leaf + cancel
+    }
+}
+
+#[test]
+fn cancellation_keeps_partial_results() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+
+    let result = scheduler.request_and_trampoline(
+        Root,
+        &Trampoline {
+            poll_loop: PollLoop::SingleThreaded,
+            cancellation: Some(cancellation().clone()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(result, Err(Cancelled));
+    assert_eq!(LEAF_RUNS.load(Ordering::SeqCst), 1);
+
+    // Root was put back into the queue, and restarts without recomputing Leaf.
+    let result = scheduler
+        .request_and_trampoline(Root, &Trampoline::default())
+        .unwrap();
+    assert_eq!(*result, 3);
+    assert_eq!(LEAF_RUNS.load(Ordering::SeqCst), 1);
+}
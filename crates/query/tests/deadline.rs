@@ -0,0 +1,104 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rokugo_query::{arena::Arena, Cancelled, Name, PollLoop, Query, Scheduler, Trampoline};
+
+static SLOW_RUNS: AtomicUsize = AtomicUsize::new(0);
+/// Deadline that [`Slow`] waits for before finishing, if any.
+static DEADLINE: Mutex<Option<Instant>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Slow(u32);
+
+impl Query for Slow {
+    const NAME: Name = Name::new("Slow");
+
+    type Result = u32;
+
+    async fn run(self, _scheduler: &Scheduler<'_>) -> Self::Result {
+        SLOW_RUNS.fetch_add(1, Ordering::SeqCst);
+        // Rather than racing a fixed sleep against the deadline, wait for the deadline itself, such
+        // that it's guaranteed to have passed once the query finishes.
+        let deadline = *DEADLINE.lock().unwrap();
+        if let Some(deadline) = deadline {
+            thread::sleep(deadline.saturating_duration_since(Instant::now()));
+        }
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Sum;
+
+impl Query for Sum {
+    const NAME: Name = Name::new("Sum");
+
+    type Result = u32;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        let mut sum = 0;
+        for i in 1..=3 {
+            sum += *scheduler.query(Slow(i)).await;
+        }
+        sum
+    }
+}
+
+#[test]
+fn deadline_keeps_partial_results() {
+    for poll_loop in [PollLoop::SingleThreaded, PollLoop::Parallel] {
+        SLOW_RUNS.store(0, Ordering::SeqCst);
+        let arena = Arena::new();
+        let scheduler = arena.alloc(Scheduler::new(&arena));
+
+        // The deadline only has to be far enough for the trampoline to start the first query.
+        let deadline = Instant::now() + Duration::from_millis(100);
+        *DEADLINE.lock().unwrap() = Some(deadline);
+        let result = scheduler.request_and_trampoline(
+            Sum,
+            &Trampoline {
+                poll_loop,
+                deadline: Some(deadline),
+                ..Default::default()
+            },
+        );
+        *DEADLINE.lock().unwrap() = None;
+        assert_eq!(result, Err(Cancelled), "{poll_loop:?}");
+        assert_eq!(SLOW_RUNS.load(Ordering::SeqCst), 1, "{poll_loop:?}");
+
+        // The slow query that finished before the deadline is not computed again.
+        let result = scheduler
+            .request_and_trampoline(
+                Sum,
+                &Trampoline {
+                    poll_loop,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(*result, 6, "{poll_loop:?}");
+        assert_eq!(SLOW_RUNS.load(Ordering::SeqCst), 3, "{poll_loop:?}");
+    }
+}
+
+#[test]
+fn passed_deadline_does_not_start_queries() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+
+    let result = scheduler.request_and_trampoline(
+        Slow(0),
+        &Trampoline {
+            deadline: Some(Instant::now()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(result, Err(Cancelled));
+    assert_eq!(scheduler.peek(&Slow(0)), None);
+}
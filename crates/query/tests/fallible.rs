@@ -0,0 +1,70 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rokugo_query::{arena::Arena, Name, Query, Scheduler, Trampoline};
+
+static FLAKY_RUNS: AtomicUsize = AtomicUsize::new(0);
+static STICKY_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// Fails the first time it's run, and succeeds afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Flaky;
+
+impl Query for Flaky {
+    const NAME: Name = Name::new("Flaky");
+
+    type Result = Result<u32, &'static str>;
+
+    async fn run(self, _scheduler: &Scheduler<'_>) -> Self::Result {
+        match FLAKY_RUNS.fetch_add(1, Ordering::SeqCst) {
+            0 => Err("first run always fails"),
+            _ => Ok(1),
+        }
+    }
+
+    fn should_memoize(result: &Self::Result) -> bool {
+        result.is_ok()
+    }
+}
+
+/// Same as [`Flaky`], but memoizes its errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Sticky;
+
+impl Query for Sticky {
+    const NAME: Name = Name::new("Sticky");
+
+    type Result = Result<u32, &'static str>;
+
+    async fn run(self, _scheduler: &Scheduler<'_>) -> Self::Result {
+        match STICKY_RUNS.fetch_add(1, Ordering::SeqCst) {
+            0 => Err("first run always fails"),
+            _ => Ok(1),
+        }
+    }
+}
+
+#[test]
+fn errors_are_retried() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+
+    let first = scheduler.request_and_trampoline(Flaky, &Trampoline::default());
+    assert_eq!(first, Ok(&Err("first run always fails")));
+    let second = scheduler.request_and_trampoline(Flaky, &Trampoline::default());
+    assert_eq!(second, Ok(&Ok(1)));
+    let third = scheduler.request_and_trampoline(Flaky, &Trampoline::default());
+    assert_eq!(third, Ok(&Ok(1)));
+    assert_eq!(FLAKY_RUNS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn errors_are_memoized_by_default() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+
+    let first = scheduler.request_and_trampoline(Sticky, &Trampoline::default());
+    assert_eq!(first, Ok(&Err("first run always fails")));
+    let second = scheduler.request_and_trampoline(Sticky, &Trampoline::default());
+    assert_eq!(second, Ok(&Err("first run always fails")));
+    assert_eq!(STICKY_RUNS.load(Ordering::SeqCst), 1);
+}
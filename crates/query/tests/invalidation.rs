@@ -0,0 +1,150 @@
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use rokugo_query::{arena::Arena, Name, PollLoop, Query, Scheduler, Trampoline};
+
+static INPUT: AtomicU32 = AtomicU32::new(1);
+static UNRELATED_RUNS: AtomicUsize = AtomicUsize::new(0);
+static FLAKY_INPUT_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// Stands in for an input to the compiler, such as a source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Input;
+
+impl Query for Input {
+    const NAME: Name = Name::new("Input");
+
+    type Result = u32;
+
+    async fn run(self, _scheduler: &Scheduler<'_>) -> Self::Result {
+        INPUT.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Double;
+
+impl Query for Double {
+    const NAME: Name = Name::new("Double");
+
+    type Result = u32;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        *scheduler.query(Input).await * 2
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Quadruple;
+
+impl Query for Quadruple {
+    const NAME: Name = Name::new("Quadruple");
+
+    type Result = u32;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        *scheduler.query(Double).await * 2
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Unrelated;
+
+impl Query for Unrelated {
+    const NAME: Name = Name::new("Unrelated");
+
+    type Result = ();
+
+    async fn run(self, _scheduler: &Scheduler<'_>) -> Self::Result {
+        UNRELATED_RUNS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Input which fails the first time it's read, and does not memoize failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct FlakyInput;
+
+impl Query for FlakyInput {
+    const NAME: Name = Name::new("FlakyInput");
+
+    type Result = Result<u32, &'static str>;
+
+    async fn run(self, _scheduler: &Scheduler<'_>) -> Self::Result {
+        match FLAKY_INPUT_RUNS.fetch_add(1, Ordering::SeqCst) {
+            0 => Err("first read always fails"),
+            _ => Ok(2),
+        }
+    }
+
+    fn should_memoize(result: &Self::Result) -> bool {
+        result.is_ok()
+    }
+}
+
+/// Memoizes whatever [`FlakyInput`] returned, including failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct DoubleFlaky;
+
+impl Query for DoubleFlaky {
+    const NAME: Name = Name::new("DoubleFlaky");
+
+    type Result = Result<u32, &'static str>;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        scheduler.query(FlakyInput).await.map(|input| input * 2)
+    }
+}
+
+#[test]
+fn invalidation() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    let trampoline = Trampoline {
+        poll_loop: PollLoop::Parallel,
+        ..Default::default()
+    };
+
+    scheduler
+        .request_and_trampoline(Unrelated, &trampoline)
+        .unwrap();
+    let result = scheduler
+        .request_and_trampoline(Quadruple, &trampoline)
+        .unwrap();
+    assert_eq!(*result, 4);
+
+    // Without invalidation, the stale result is reused.
+    INPUT.store(2, Ordering::SeqCst);
+    let result = scheduler
+        .request_and_trampoline(Quadruple, &trampoline)
+        .unwrap();
+    assert_eq!(*result, 4);
+
+    scheduler.invalidate(&Input);
+    let result = scheduler
+        .request_and_trampoline(Quadruple, &trampoline)
+        .unwrap();
+    assert_eq!(*result, 8);
+
+    scheduler
+        .request_and_trampoline(Unrelated, &trampoline)
+        .unwrap();
+    assert_eq!(UNRELATED_RUNS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn invalidating_unmemoized_error() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    let trampoline = Trampoline::default();
+
+    let result = scheduler.request_and_trampoline(DoubleFlaky, &trampoline);
+    assert_eq!(result, Ok(&Err("first read always fails")));
+    let result = scheduler.request_and_trampoline(DoubleFlaky, &trampoline);
+    assert_eq!(result, Ok(&Err("first read always fails")));
+
+    // The failed result of the input was never memoized, but the queries depending on it must
+    // still be invalidated along with it.
+    scheduler.invalidate(&FlakyInput);
+    let result = scheduler.request_and_trampoline(DoubleFlaky, &trampoline);
+    assert_eq!(result, Ok(&Ok(4)));
+    assert_eq!(FLAKY_INPUT_RUNS.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,69 @@
+use std::sync::Mutex;
+
+use rokugo_query::{arena::Arena, Name, Query, QueueOrder, Scheduler, Trampoline};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Node {
+    Root,
+    Branch(u32),
+    Leaf(u32),
+}
+
+static COMPLETED: Mutex<Vec<Node>> = Mutex::new(vec![]);
+
+impl Query for Node {
+    const NAME: Name = Name::new("Node");
+
+    type Result = ();
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        match self {
+            Node::Root => {
+                scheduler
+                    .query_all([Node::Branch(0), Node::Branch(1), Node::Leaf(0)])
+                    .await;
+            }
+            Node::Branch(n) => {
+                scheduler
+                    .query_all([Node::Leaf(n * 10 + 1), Node::Leaf(n * 10 + 2)])
+                    .await;
+            }
+            Node::Leaf(_) => (),
+        }
+        COMPLETED.lock().unwrap().push(self);
+    }
+}
+
+fn completion_order() -> Vec<Node> {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    scheduler
+        .request_and_trampoline(
+            Node::Root,
+            &Trampoline {
+                queue_order: QueueOrder::FirstInFirstOut,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    std::mem::take(&mut *COMPLETED.lock().unwrap())
+}
+
+#[test]
+fn first_in_first_out() {
+    let order = completion_order();
+    assert_eq!(
+        order,
+        [
+            Node::Leaf(0),
+            Node::Leaf(1),
+            Node::Leaf(2),
+            Node::Leaf(11),
+            Node::Leaf(12),
+            Node::Branch(0),
+            Node::Branch(1),
+            Node::Root,
+        ]
+    );
+    assert_eq!(completion_order(), order);
+}
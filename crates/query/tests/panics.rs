@@ -0,0 +1,92 @@
+use rokugo_diagnostic::Severity;
+use rokugo_query::{arena::Arena, Name, PollLoop, Query, Scheduler, Trampoline};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Panicking;
+
+impl Query for Panicking {
+    const NAME: Name = Name::new("Panicking");
+
+    type Result = u32;
+
+    async fn run(self, _scheduler: &Scheduler<'_>) -> Self::Result {
+        panic!("deliberate panic")
+    }
+}
+
+/// Awaits [`Panicking`], so it can never finish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct AwaitsPanicking;
+
+impl Query for AwaitsPanicking {
+    const NAME: Name = Name::new("AwaitsPanicking");
+
+    type Result = u32;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        *scheduler.query(Panicking).await + 1
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Independent(u32);
+
+impl Query for Independent {
+    const NAME: Name = Name::new("Independent");
+
+    type Result = u32;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        match self.0 {
+            0 => 0,
+            n => *scheduler.query(Independent(n - 1)).await + 1,
+        }
+    }
+}
+
+fn panics_are_reported(poll_loop: PollLoop) {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    let trampoline = Trampoline {
+        poll_loop,
+        ..Default::default()
+    };
+
+    let _awaits_panicking = scheduler.query(AwaitsPanicking);
+    let _independent = scheduler.query(Independent(10));
+    assert_eq!(scheduler.trampoline(&trampoline), Ok(()));
+
+    let diagnostics = scheduler.take_diagnostics();
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:#?}");
+    assert_eq!(diagnostics[0].severity, Severity::Bug);
+    assert!(diagnostics[0].message.contains("Panicking"));
+    assert!(diagnostics[0]
+        .notes
+        .iter()
+        .any(|note| note.message.contains("deliberate panic")));
+
+    // Queries not depending on the panicking query are computed as usual.
+    let independent = scheduler
+        .request_and_trampoline(Independent(10), &trampoline)
+        .unwrap();
+    assert_eq!(*independent, 10);
+    assert!(scheduler.take_diagnostics().is_empty());
+}
+
+#[test]
+fn panics_are_reported_single_threaded() {
+    panics_are_reported(PollLoop::SingleThreaded);
+}
+
+#[test]
+fn panics_are_reported_parallel() {
+    panics_are_reported(PollLoop::Parallel);
+}
+
+#[test]
+#[should_panic = "requested query \"Panicking\" panicked"]
+fn requesting_panicking_query() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    let _ = scheduler.request_and_trampoline(Panicking, &Trampoline::default());
+}
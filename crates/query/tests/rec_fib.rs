@@ -1,4 +1,6 @@
-use rokugo_query::{arena::Arena, Name, PollLoop, Query, Scheduler, Trampoline};
+use std::{collections::HashSet, sync::Mutex, thread, thread::ThreadId};
+
+use rokugo_query::{arena::Arena, Name, PollLoop, Query, Scheduler, SchedulerStats, Trampoline};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Fib(u32);
@@ -26,25 +28,148 @@ impl Query for Fib {
 fn rec_fib() {
     let arena = Arena::new();
     let scheduler = arena.alloc(Scheduler::new(&arena));
-    let fib = scheduler.request_and_trampoline(Fib(30), &Trampoline::default());
+    let fib = scheduler
+        .request_and_trampoline(Fib(30), &Trampoline::default())
+        .unwrap();
     assert_eq!(*fib, 832040);
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct FibBatch(u32, u32);
+
+impl Query for FibBatch {
+    const NAME: Name = Name::new("FibBatch");
+
+    type Result = Vec<u32>;
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        let FibBatch(start, end) = self;
+        let results = scheduler.query_all((start..=end).map(Fib)).await;
+        results.into_iter().copied().collect()
+    }
+}
+
+#[test]
+fn query_all() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    let fibs = scheduler
+        .request_and_trampoline(FibBatch(10, 15), &Trampoline::default())
+        .unwrap();
+    assert_eq!(fibs, &[55, 89, 144, 233, 377, 610]);
+}
+
 #[test]
 fn threading() {
     let arena = Arena::new();
     let scheduler = arena.alloc(Scheduler::new(&arena));
-    let fib_st = scheduler.request_and_trampoline(
-        Fib(30),
-        &Trampoline {
-            poll_loop: PollLoop::SingleThreaded,
-        },
-    );
-    let fib_mt = scheduler.request_and_trampoline(
-        Fib(30),
-        &Trampoline {
-            poll_loop: PollLoop::Parallel,
-        },
-    );
+    let fib_st = scheduler
+        .request_and_trampoline(
+            Fib(30),
+            &Trampoline {
+                poll_loop: PollLoop::SingleThreaded,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let fib_mt = scheduler
+        .request_and_trampoline(
+            Fib(30),
+            &Trampoline {
+                poll_loop: PollLoop::Parallel,
+                ..Default::default()
+            },
+        )
+        .unwrap();
     assert_eq!(fib_st, fib_mt);
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct RecordThread(u32);
+
+static THREADS: Mutex<Option<HashSet<ThreadId>>> = Mutex::new(None);
+
+impl Query for RecordThread {
+    const NAME: Name = Name::new("RecordThread");
+
+    type Result = ();
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        THREADS
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashSet::new)
+            .insert(thread::current().id());
+        // Queries at or above 1000 are leaves, requested by the chain below them.
+        if (1..1000).contains(&self.0) {
+            let l = scheduler.query(RecordThread(self.0 - 1));
+            let r = scheduler.query(RecordThread(self.0 + 1000));
+            l.await;
+            r.await;
+        }
+    }
+}
+
+#[test]
+fn num_threads() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    // Both trampolines share one thread pool, so only two threads are ever used between them.
+    for start in [100, 200] {
+        scheduler
+            .request_and_trampoline(
+                RecordThread(start),
+                &Trampoline {
+                    poll_loop: PollLoop::Parallel,
+                    num_threads: Some(2),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+    }
+    let threads = THREADS.lock().unwrap().take().unwrap();
+    assert!(threads.len() <= 2, "{threads:?}");
+}
+
+#[test]
+fn stats() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    scheduler
+        .request_and_trampoline(Fib(10), &Trampoline::default())
+        .unwrap();
+
+    // Fib(0) through Fib(10) are computed once each. Every Fib(n) for n >= 2 requests two other
+    // queries, which along with the initial request gives 1 + 2 * 9 requests in total.
+    let stats = scheduler.stats();
+    assert_eq!(stats.enqueued, 11);
+    assert_eq!(stats.computed, 11);
+    assert_eq!(stats.cache_hits + stats.cache_misses, 19);
+    assert!(stats.cache_misses >= 11);
+
+    scheduler
+        .request_and_trampoline(Fib(10), &Trampoline::default())
+        .unwrap();
+    assert_eq!(
+        scheduler.stats(),
+        SchedulerStats {
+            cache_hits: stats.cache_hits + 1,
+            ..stats
+        }
+    );
+}
+
+#[test]
+fn peek() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    assert_eq!(scheduler.peek(&Fib(10)), None);
+    assert_eq!(scheduler.stats(), SchedulerStats::default());
+
+    scheduler
+        .request_and_trampoline(Fib(10), &Trampoline::default())
+        .unwrap();
+    assert_eq!(scheduler.peek(&Fib(10)), Some(&55));
+    assert_eq!(scheduler.peek(&Fib(5)), Some(&5));
+    assert_eq!(scheduler.peek(&Fib(11)), None);
+}
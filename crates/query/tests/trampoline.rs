@@ -0,0 +1,128 @@
+use std::future;
+
+use rokugo_query::{arena::Arena, Name, PollLoop, Query, Scheduler, Trampoline};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct NeverFinishes;
+
+impl Query for NeverFinishes {
+    const NAME: Name = Name::new("NeverFinishes");
+
+    type Result = ();
+
+    async fn run(self, _scheduler: &Scheduler<'_>) -> Self::Result {
+        future::pending::<()>().await;
+    }
+}
+
+#[test]
+#[should_panic(expected = "queries still pending: NeverFinishes")]
+fn watchdog_single_threaded() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    scheduler
+        .request_and_trampoline(
+            NeverFinishes,
+            &Trampoline {
+                poll_loop: PollLoop::SingleThreaded,
+                max_iterations: Some(3),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "query scheduler made no progress for 3 iterations")]
+fn watchdog_parallel() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    scheduler
+        .request_and_trampoline(
+            NeverFinishes,
+            &Trampoline {
+                poll_loop: PollLoop::Parallel,
+                max_iterations: Some(3),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct AwaitsItself;
+
+impl Query for AwaitsItself {
+    const NAME: Name = Name::new("AwaitsItself");
+
+    type Result = ();
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        scheduler.query(AwaitsItself).await;
+    }
+}
+
+/// Awaits `Even(n - 1)`. `Even(0)` awaits `Odd(1)`, which closes the cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Odd(u32);
+
+impl Query for Odd {
+    const NAME: Name = Name::new("Odd");
+
+    type Result = ();
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        scheduler.query(Even(self.0 - 1)).await;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Even(u32);
+
+impl Query for Even {
+    const NAME: Name = Name::new("Even");
+
+    type Result = ();
+
+    async fn run(self, scheduler: &Scheduler<'_>) -> Self::Result {
+        if self.0 == 0 {
+            scheduler.query(Odd(1)).await;
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "cycle: AwaitsItself -> AwaitsItself")]
+fn self_cycle_single_threaded() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    scheduler
+        .request_and_trampoline(AwaitsItself, &Trampoline::default())
+        .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "queries await each other in a cycle")]
+fn mutual_cycle_parallel() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    scheduler
+        .request_and_trampoline(
+            Odd(1),
+            &Trampoline {
+                poll_loop: PollLoop::Parallel,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "Odd(1) -> Even(0) -> Odd(1)")]
+fn mutual_cycle_single_threaded() {
+    let arena = Arena::new();
+    let scheduler = arena.alloc(Scheduler::new(&arena));
+    scheduler
+        .request_and_trampoline(Odd(1), &Trampoline::default())
+        .unwrap();
+}
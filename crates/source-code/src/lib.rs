@@ -1,6 +1,8 @@
 //! Source code storage and handling.
 
-use std::ops::Range;
+use std::{hash::Hasher, ops::Range, sync::OnceLock};
+
+use rustc_hash::FxHasher;
 
 /// Loaded source file.
 #[derive(Debug, Clone)]
@@ -12,13 +14,15 @@ pub struct File {
 /// Unique identifier used to look up files inside [`Sources`].
 ///
 /// The representation of this identifier is unspecified and may change between compilations.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct FileId(usize);
 
 /// Set of source files indexable by [`FileId`]s.
 #[derive(Debug, Clone, Default)]
 pub struct Sources {
     files: Vec<File>,
+    content_hashes: Vec<OnceLock<u64>>,
+    line_starts: Vec<OnceLock<Vec<usize>>>,
 }
 
 impl Sources {
@@ -26,12 +30,105 @@ impl Sources {
     pub fn add(&mut self, file: File) -> FileId {
         let id = FileId(self.files.len());
         self.files.push(file);
+        self.content_hashes.push(OnceLock::new());
+        self.line_starts.push(OnceLock::new());
         id
     }
 
+    /// Get a source file from the set, or `None` if the ID does not belong to this set.
+    ///
+    /// IDs from other sets may still point to a file in this one, so this is only a best-effort
+    /// check.
+    pub fn try_get(&self, id: FileId) -> Option<&File> {
+        self.files.get(id.0)
+    }
+
     /// Get a source file from the set.
+    ///
+    /// # Panics
+    /// If the ID does not belong to this set. See [`try_get`][Self::try_get].
     pub fn get(&self, id: FileId) -> &File {
-        &self.files[id.0]
+        self.try_get(id)
+            .unwrap_or_else(|| panic!("{id:?} does not belong to this set of sources"))
+    }
+
+    /// Replace the source code of a file in the set, keeping its ID.
+    ///
+    /// Byte offsets computed against the old source code, such as in [`SourceSpan`]s, are not
+    /// updated, and may no longer point to the right place (or to a character boundary at all).
+    ///
+    /// # Panics
+    /// If the ID does not belong to this set. See [`try_get`][Self::try_get].
+    pub fn replace(&mut self, id: FileId, source: String) {
+        let file = self
+            .files
+            .get_mut(id.0)
+            .unwrap_or_else(|| panic!("{id:?} does not belong to this set of sources"));
+        file.source = source;
+        self.content_hashes[id.0] = OnceLock::new();
+        self.line_starts[id.0] = OnceLock::new();
+    }
+
+    /// Get a hash of a source file's contents.
+    ///
+    /// Unlike [`FileId`]s, the hash only depends on the source code, so files with identical
+    /// contents hash the same, even across compilations. The hash is computed once and cached.
+    ///
+    /// # Panics
+    /// If the ID does not belong to this set. See [`try_get`][Self::try_get].
+    pub fn content_hash(&self, id: FileId) -> u64 {
+        let source = &self.get(id).source;
+        *self.content_hashes[id.0].get_or_init(|| {
+            let mut hasher = FxHasher::default();
+            hasher.write(source.as_bytes());
+            hasher.finish()
+        })
+    }
+
+    /// Byte offsets at which each line of a source file starts. Computed once and cached.
+    fn line_starts(&self, id: FileId) -> &[usize] {
+        let source = &self.get(id).source;
+        self.line_starts[id.0].get_or_init(|| {
+            let newlines = source.match_indices('\n').map(|(i, _)| i + 1);
+            [0].into_iter().chain(newlines).collect()
+        })
+    }
+
+    /// Get the line containing the given byte offset in a source file. Lines are counted from 0.
+    ///
+    /// Offsets past the end of the file belong to the last line.
+    pub fn line_of_offset(&self, id: FileId, byte: usize) -> usize {
+        self.line_starts(id).partition_point(|&start| start <= byte) - 1
+    }
+
+    /// Get the text of a line in a source file, or `None` if the file does not have that many
+    /// lines. Lines are counted from 0.
+    ///
+    /// The line break at the end of the line is not included. This applies to both `\n` and
+    /// `\r\n` line breaks, so that the text is the same regardless of which one the file uses.
+    pub fn line_text(&self, id: FileId, line: usize) -> Option<&str> {
+        let line_starts = self.line_starts(id);
+        let source = &self.get(id).source;
+        let start = *line_starts.get(line)?;
+        let end = line_starts.get(line + 1).copied().unwrap_or(source.len());
+        let text = &source[start..end];
+        Some(match text.strip_suffix('\n') {
+            Some(text) => text.strip_suffix('\r').unwrap_or(text),
+            None => text,
+        })
+    }
+
+    /// Construct a set containing a single source file, returning the set and the file's ID.
+    ///
+    /// Meant for tests, which usually only need to operate on one file.
+    #[cfg(feature = "test-support")]
+    pub fn with_one(filename: impl Into<String>, source: impl Into<String>) -> (Self, FileId) {
+        let mut sources = Self::default();
+        let file_id = sources.add(File {
+            filename: filename.into(),
+            source: source.into(),
+        });
+        (sources, file_id)
     }
 }
 
@@ -0,0 +1,28 @@
+use rokugo_source_code::Sources;
+
+#[test]
+fn identical_contents() {
+    let (a_sources, a) = Sources::with_one("a.rk", "let x = 1");
+    let (b_sources, b) = Sources::with_one("b.rk", "let x = 1");
+    assert_eq!(a_sources.content_hash(a), b_sources.content_hash(b));
+}
+
+#[test]
+fn one_byte_change() {
+    let (a_sources, a) = Sources::with_one("test.rk", "let x = 1");
+    let (b_sources, b) = Sources::with_one("test.rk", "let x = 2");
+    assert_ne!(a_sources.content_hash(a), b_sources.content_hash(b));
+}
+
+#[test]
+fn cached() {
+    let (sources, a) = Sources::with_one("test.rk", "let x = 1");
+    assert_eq!(sources.content_hash(a), sources.content_hash(a));
+}
+
+#[test]
+#[should_panic = "does not belong to this set of sources"]
+fn foreign() {
+    let (_foreign, id) = Sources::with_one("test.rk", "let x = 1");
+    Sources::default().content_hash(id);
+}
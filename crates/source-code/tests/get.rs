@@ -0,0 +1,15 @@
+use rokugo_source_code::Sources;
+
+#[test]
+fn try_get() {
+    let (sources, id) = Sources::with_one("test.rk", "let x = 1");
+    assert_eq!(sources.try_get(id).unwrap().source, "let x = 1");
+    assert!(Sources::default().try_get(id).is_none());
+}
+
+#[test]
+#[should_panic = "does not belong to this set of sources"]
+fn get_foreign() {
+    let (_foreign, id) = Sources::with_one("test.rk", "let x = 1");
+    Sources::default().get(id);
+}
@@ -0,0 +1,28 @@
+use rokugo_source_code::Sources;
+
+#[test]
+fn line_text() {
+    let (sources, id) = Sources::with_one("test.rk", "let x = 1\r\n\nlet y = 2\nx + y");
+    assert_eq!(sources.line_text(id, 0), Some("let x = 1"));
+    assert_eq!(sources.line_text(id, 1), Some(""));
+    assert_eq!(sources.line_text(id, 2), Some("let y = 2"));
+    assert_eq!(sources.line_text(id, 3), Some("x + y"));
+    assert_eq!(sources.line_text(id, 4), None);
+}
+
+#[test]
+fn trailing_newline() {
+    let (sources, id) = Sources::with_one("test.rk", "a\n");
+    assert_eq!(sources.line_text(id, 0), Some("a"));
+    assert_eq!(sources.line_text(id, 1), Some(""));
+    assert_eq!(sources.line_text(id, 2), None);
+}
+
+#[test]
+fn line_of_offset() {
+    let (sources, id) = Sources::with_one("test.rk", "ab\ncd\n\nef");
+    let lines: Vec<_> = (0..=10)
+        .map(|byte| sources.line_of_offset(id, byte))
+        .collect();
+    assert_eq!(lines, [0, 0, 0, 1, 1, 1, 2, 3, 3, 3, 3]);
+}
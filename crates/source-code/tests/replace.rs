@@ -0,0 +1,25 @@
+use rokugo_source_code::Sources;
+
+#[test]
+fn replace() {
+    let (mut sources, id) = Sources::with_one("a.rk", "let x = 1\nlet y = 2");
+    let (other_sources, other) = Sources::with_one("b.rk", "let z = 3");
+    // Fill in the caches, so that replacing has to invalidate them.
+    let old_hash = sources.content_hash(id);
+    assert_eq!(sources.line_text(id, 1), Some("let y = 2"));
+
+    sources.replace(id, "let z = 3".into());
+    assert_eq!(sources.get(id).source, "let z = 3");
+    assert_eq!(sources.get(id).filename, "a.rk");
+    assert_ne!(sources.content_hash(id), old_hash);
+    assert_eq!(sources.content_hash(id), other_sources.content_hash(other));
+    assert_eq!(sources.line_text(id, 0), Some("let z = 3"));
+    assert_eq!(sources.line_text(id, 1), None);
+}
+
+#[test]
+#[should_panic = "does not belong to this set of sources"]
+fn replace_foreign() {
+    let (_foreign, id) = Sources::with_one("a.rk", "");
+    Sources::default().replace(id, "let x = 1".into());
+}